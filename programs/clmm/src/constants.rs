@@ -6,24 +6,72 @@ pub const ANCHOR_SIZE: u32 = 8;
 #[constant]
 pub const SEED: &str = "anchor";
 
+#[constant]
+pub const AMM_CONFIG_SEED: &str = "amm_config";
+
+#[constant]
+pub const SUPPORT_MINT_SEED: &str = "support_mint";
+
 #[constant]
 pub const POOL_SEED: &str = "clmm_pool";
 
 #[constant]
 pub const POOL_VAULT_SEED: &str = "token_vault";
 
+#[constant]
+pub const POOL_REWARD_VAULT_SEED: &str = "pool_reward_vault";
+
 #[constant]
 pub const TICK_ARRAY_SEED: &str = "clmm_tick_array";
 
 #[constant]
 pub const POSITION_SEED: &str = "clmm_position";
 
+#[constant]
+pub const LIMIT_ORDER_SEED: &str = "clmm_limit_order";
+
+/// Metadata attached to every position NFT
+#[constant]
+pub const POSITION_NFT_NAME: &str = "CLMM Position";
+#[constant]
+pub const POSITION_NFT_SYMBOL: &str = "CLMM-POS";
+#[constant]
+pub const POSITION_NFT_URI: &str = "";
+
 #[constant]
 pub const TICK_ARRAY_BITMAP_SEED: &str = "tick_array_bitmap";
 
+#[constant]
+pub const OBSERVATION_SEED: &str = "observation";
+
+/// Number of observations kept in the ring buffer of each pool
+#[constant]
+pub const OBSERVATION_NUM: u32 = 100;
+
+/// Number of reward tokens a pool can emit at the same time
+#[constant]
+pub const REWARD_NUM: u32 = 3;
+
 #[constant]
 pub const FEE_RATE_DENOMINATOR_VALUE: u32 = 1_000_000;
 
+/// Swaps closer together than this keep the volatility reference of the previous swap, in seconds
+#[constant]
+pub const DYNAMIC_FEE_FILTER_PERIOD: u64 = 30;
+/// After this long without a swap the volatility is forgotten, in seconds
+#[constant]
+pub const DYNAMIC_FEE_DECAY_PERIOD: u64 = 600;
+/// Share of the volatility carried over between swaps, in units of FEE_RATE_DENOMINATOR_VALUE
+#[constant]
+pub const DYNAMIC_FEE_REDUCTION_FACTOR: u32 = 500_000;
+/// Variable fee added per tick of accumulated volatility, in hundredths of a bip
+#[constant]
+pub const DYNAMIC_FEE_CONTROL: u32 = 10;
+/// Most tick spacings of volatility a fee tier may take to reach its max_dynamic_fee_rate.
+/// Swap steps are split at every tick spacing the fee changes in, so this bounds their number.
+#[constant]
+pub const DYNAMIC_FEE_MAX_SPACINGS: u32 = 32;
+
 #[constant]
 pub const TICK_ARRAY_SIZE: u32 = 60;
 
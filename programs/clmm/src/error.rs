@@ -46,4 +46,86 @@ pub enum ErrorCode {
 
     #[msg("Remaining account is missed")]
     RemainingAccountMissed,
+
+    // Swap
+    #[msg("Swap amount must not be zero")]
+    ZeroAmountSpecified,
+    #[msg("sqrt_price_limit_x64 is out of range or on the wrong side of the current price")]
+    SqrtPriceLimitOverflow,
+    #[msg("Input and output vaults must be the two vaults of the pool")]
+    InvalidInputPoolVault,
+    #[msg("Too little output received")]
+    TooLittleOutputReceived,
+    #[msg("Too much input paid")]
+    TooMuchInputPaid,
+    #[msg("Tick array does not belong to the pool or is out of swap order")]
+    InvalidTickArray,
+    #[msg("Not enough tick array accounts to complete the swap")]
+    NotEnoughTickArrayAccount,
+
+    // Position
+    #[msg("Remove all liquidity and collect all fees and rewards before closing the position")]
+    ClosePositionErr,
+
+    // Admin
+    #[msg("Signer is not the admin")]
+    NotApproved,
+    #[msg("Fee rate exceeds FEE_RATE_DENOMINATOR_VALUE")]
+    InvalidFeeRate,
+
+    // Position NFT
+    #[msg("Signer does not hold the position NFT")]
+    NotPositionNftHolder,
+
+    // Token-2022
+    #[msg("Failed to calculate the transfer fee")]
+    TransferFeeCalculateErr,
+    #[msg("Mints with a permanent delegate are not supported")]
+    MintPermanentDelegateNotSupported,
+    #[msg("Non-transferable mints are not supported")]
+    MintNonTransferableNotSupported,
+    #[msg("Mints whose accounts are frozen by default are not supported")]
+    MintDefaultFrozenNotSupported,
+    #[msg("Mints with a transfer hook are not supported")]
+    MintTransferHookNotSupported,
+    #[msg("Mint has an unsupported extension")]
+    MintExtensionNotSupported,
+
+    // Oracle
+    #[msg("Requested time is older than the oldest observation")]
+    ObservationTooOld,
+    #[msg("TWAP window must be greater than zero")]
+    InvalidTwapWindow,
+    #[msg("Tick is not initialized")]
+    TickNotInitialized,
+
+    // Rewards
+    #[msg("All reward slots of the pool are in use")]
+    FullRewardInfo,
+    #[msg("The reward token is already emitted by the pool")]
+    RewardTokenAlreadyInUse,
+    #[msg("Invalid reward index")]
+    InvalidRewardIndex,
+    #[msg("Invalid reward open_time, end_time or emissions")]
+    InvalidRewardParams,
+    #[msg("Reward growth overflow")]
+    RewardGrowthOverflow,
+    #[msg("Reward accounts are missing or in the wrong order")]
+    InvalidRewardInputAccount,
+
+    // Flash
+    #[msg("Flash loan was not paid back with the fee")]
+    FlashLoanNotRepaid,
+    #[msg("The flash loan callback cannot be this program")]
+    InvalidFlashCallback,
+
+    // Limit orders
+    #[msg("A limit order must rest on a tick the price has yet to reach")]
+    InvalidLimitOrderTick,
+    #[msg("The tick already holds limit orders selling the other token")]
+    LimitOrderSideConflict,
+    #[msg("The shares of the limit orders on the tick cannot be scaled down any further")]
+    LimitOrderSharesOverflow,
+    #[msg("The limit order is too small for the partly filled orders on its tick")]
+    LimitOrderTooSmall,
 }
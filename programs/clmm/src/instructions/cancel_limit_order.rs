@@ -0,0 +1,140 @@
+use anchor_lang::{prelude::*, Accounts};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::constants::{LIMIT_ORDER_SEED, TICK_ARRAY_SEED};
+use crate::error::ErrorCode;
+use crate::libraries::{limit_order_math, tick_math};
+use crate::state::{LimitOrderState, PoolState, TickStateArray, TickStateArrayBitMap};
+use crate::util;
+
+#[derive(Accounts)]
+#[instruction(tick_index: i32)]
+pub struct CancelLimitOrder<'info> {
+    /// Receives the rent of the order account
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            LIMIT_ORDER_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &tick_index.to_le_bytes(),
+            owner.key().as_ref()],
+        bump,
+        close = owner,
+    )]
+    pub limit_order: Box<Account<'info, LimitOrderState>>,
+
+    /// Stores the order's tick
+    #[account(
+        mut,
+        seeds = [
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &TickStateArray::get_array_start_index(
+                tick_index, pool_state.load()?.tick_spacing).to_le_bytes(),
+        ],
+        bump
+    )]
+    pub tick_array: AccountLoader<'info, TickStateArray>,
+
+    #[account(mut, constraint = token_vault_0.key() == pool_state.load()?.token_vault_0)]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, constraint = token_vault_1.key() == pool_state.load()?.token_vault_1)]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::mint = token_vault_0.mint)]
+    pub recipient_token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::mint = token_vault_1.mint)]
+    pub recipient_token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = token_vault_0.mint, mint::token_program = token_program_0)]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(address = token_vault_1.mint, mint::token_program = token_program_1)]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub token_program_0: Interface<'info, TokenInterface>,
+    pub token_program_1: Interface<'info, TokenInterface>,
+
+    // remaining accounts
+    // tick_array_bitmap, required when the tick array gets uninitialized
+    // transfer-hook program and extra accounts of hooked mints
+}
+
+/// Takes the order off its tick, paying out the unfilled remainder together with
+/// whatever has been filled and not collected yet, and closes the order account
+pub fn cancel_limit_order_impl<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CancelLimitOrder<'info>>,
+        tick_index: i32,
+    ) -> Result<()> {
+    let tick_spacing = ctx.accounts.pool_state.load()?.tick_spacing;
+    let limit_order = &mut ctx.accounts.limit_order;
+
+    // === Settle The Filled Part ===
+    let (unfilled, flipped, filled) = {
+        let mut tick_array = ctx.accounts.tick_array.load_mut()?;
+        let tick_state = tick_array.get_tick_state(tick_index)?;
+        let filled = limit_order.settle_filled(&tick_state);
+
+        // === Take The Unfilled Part Off The Tick ===
+        if limit_order.is_open(&tick_state) {
+            let (shares, share_scale) = (limit_order.shares, limit_order.share_scale);
+            // the refund never exceeds what the order put in and has not been paid out for
+            let unfilled_max = limit_order.get_unfilled_max();
+            let (unfilled, flipped) = tick_array.update_orders(tick_index, |tick_state| {
+                Ok(tick_state.remove_order(shares, share_scale, unfilled_max))
+            })?;
+            (unfilled, flipped, filled)
+        } else {
+            (0, false, filled)
+        }
+    };
+    let filled_out = limit_order_math::get_amount_out_at_price(
+        tick_math::get_sqrt_price_at_tick(tick_index)?,
+        filled,
+        !limit_order.zero_for_one,
+    )
+    .ok_or(ErrorCode::MaxTokenOverflow)?;
+
+    // === Update TickStateArrayBitmap ===
+    if flipped {
+        let bitmap_key = ctx.accounts.pool_state.load()?.tick_array_bitmap;
+        let bitmap_info = ctx
+            .remaining_accounts
+            .iter()
+            .find(|account_info| account_info.key() == bitmap_key)
+            .ok_or(ErrorCode::RemainingAccountMissed)?;
+        util::account_map_mut(
+            bitmap_info,
+            |bitmap: &mut TickStateArrayBitMap| bitmap.flip(tick_index, tick_spacing))??;
+    }
+
+    // === Transfer Tokens ===
+    // a zero_for_one order sells token_1 and is filled in token_0
+    let (amount_0, amount_1) = if limit_order.zero_for_one {
+        (filled_out, unfilled)
+    } else {
+        (unfilled, filled_out)
+    };
+    util::transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_0,
+        &ctx.accounts.recipient_token_account_0,
+        &ctx.accounts.vault_0_mint,
+        &ctx.accounts.token_program_0,
+        ctx.remaining_accounts,
+        amount_0,
+    )?;
+    util::transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_1,
+        &ctx.accounts.recipient_token_account_1,
+        &ctx.accounts.vault_1_mint,
+        &ctx.accounts.token_program_1,
+        ctx.remaining_accounts,
+        amount_1,
+    )?;
+    Ok(())
+}
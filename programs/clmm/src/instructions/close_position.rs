@@ -0,0 +1,74 @@
+use anchor_lang::{prelude::*, Accounts};
+use anchor_spl::token::{self, Burn, CloseAccount, Mint, Token, TokenAccount};
+
+use crate::constants::POSITION_SEED;
+use crate::state::{PoolState, PositionState};
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+#[instruction(tick_lower: i32, tick_upper: i32)]
+pub struct ClosePosition<'info> {
+    /// Receives the rent of the position and NFT token accounts
+    #[account(mut)]
+    pub lp: Signer<'info>,
+
+    #[account(mut, address = nft_account.mint)]
+    pub nft_mint: Box<Account<'info, Mint>>,
+
+    /// Holding the position NFT is what authorises closing the position
+    #[account(
+        mut,
+        token::authority = lp,
+        constraint = nft_account.amount == 1 @ ErrorCode::NotPositionNftHolder,
+    )]
+    pub nft_account: Box<Account<'info, TokenAccount>>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &tick_lower.to_le_bytes(),
+            &tick_upper.to_le_bytes(),
+            nft_mint.key().as_ref()],
+        bump,
+        close = lp,
+    )]
+    pub position: Box<Account<'info, PositionState>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Closes an empty position, burning its NFT and closing the NFT token account
+pub fn close_position_impl(ctx: Context<ClosePosition>, _tick_lower: i32, _tick_upper: i32) -> Result<()> {
+    let position = &ctx.accounts.position;
+    require!(
+        position.liquidity == 0
+            && position.tokens_owed_0 == 0
+            && position.tokens_owed_1 == 0
+            && position.reward_infos.iter().all(|reward_info| reward_info.reward_amount_owed == 0),
+        ErrorCode::ClosePositionErr
+    );
+
+    token::burn(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.nft_mint.to_account_info(),
+                from: ctx.accounts.nft_account.to_account_info(),
+                authority: ctx.accounts.lp.to_account_info(),
+            },
+        ),
+        1,
+    )?;
+    token::close_account(CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.nft_account.to_account_info(),
+            destination: ctx.accounts.lp.to_account_info(),
+            authority: ctx.accounts.lp.to_account_info(),
+        },
+    ))
+}
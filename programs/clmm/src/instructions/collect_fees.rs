@@ -0,0 +1,129 @@
+use anchor_lang::{prelude::*, Accounts};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::constants::{POSITION_SEED, TICK_ARRAY_SEED};
+use crate::state::{self, PoolState, PositionState, TickStateArray};
+use crate::error::ErrorCode;
+use crate::util;
+
+#[derive(Accounts)]
+#[instruction(tick_lower: i32, tick_upper: i32)]
+pub struct CollectFees<'info> {
+    pub lp: Signer<'info>,
+
+    /// Holding the position NFT is what authorises changes to the position
+    #[account(
+        token::authority = lp,
+        constraint = nft_account.amount == 1 @ ErrorCode::NotPositionNftHolder,
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &tick_lower.to_le_bytes(),
+            &tick_upper.to_le_bytes(),
+            nft_account.mint.as_ref()],
+        bump,
+    )]
+    pub position: Box<Account<'info, PositionState>>,
+
+    #[account(mut, constraint = token_vault_0.key() == pool_state.load()?.token_vault_0)]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, constraint = token_vault_1.key() == pool_state.load()?.token_vault_1)]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::mint = token_vault_0.mint)]
+    pub recipient_token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::mint = token_vault_1.mint)]
+    pub recipient_token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = token_vault_0.mint, mint::token_program = token_program_0)]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(address = token_vault_1.mint, mint::token_program = token_program_1)]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Stores the position's lower tick
+    #[account(
+        seeds = [
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &TickStateArray::get_array_start_index(
+                tick_lower, pool_state.load()?.tick_spacing).to_le_bytes(),
+        ],
+        bump
+    )]
+    pub tick_array_lower: AccountLoader<'info, TickStateArray>,
+
+    /// Stores the position's upper tick
+    #[account(
+        seeds = [
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &TickStateArray::get_array_start_index(
+                tick_upper, pool_state.load()?.tick_spacing).to_le_bytes(),
+        ],
+        bump
+    )]
+    pub tick_array_upper: AccountLoader<'info, TickStateArray>,
+
+    pub token_program_0: Interface<'info, TokenInterface>,
+    pub token_program_1: Interface<'info, TokenInterface>,
+
+    // remaining accounts
+    // transfer-hook program and extra accounts of hooked mints
+}
+
+/// Pays out up to the requested amounts of the fees owed to the position
+pub fn collect_fees_impl<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CollectFees<'info>>,
+        tick_lower: i32,
+        tick_upper: i32,
+        amount_0_requested: u64,
+        amount_1_requested: u64,
+    ) -> Result<()> {
+    // === Settle Fees Earned Since The Last Update ===
+    if ctx.accounts.position.liquidity > 0 {
+        let pool_state = ctx.accounts.pool_state.load()?;
+        let (fee_growth_inside_0_x64, fee_growth_inside_1_x64) = state::get_fee_growth_inside(
+            &ctx.accounts.tick_array_lower.load()?.get_tick_state(tick_lower)?,
+            &ctx.accounts.tick_array_upper.load()?.get_tick_state(tick_upper)?,
+            tick_lower,
+            tick_upper,
+            pool_state.tick_current,
+            pool_state.fee_growth_global_0_x64,
+            pool_state.fee_growth_global_1_x64,
+        );
+        ctx.accounts.position.update(0, fee_growth_inside_0_x64, fee_growth_inside_1_x64)?;
+    }
+
+    // === Collect ===
+    let position = &mut ctx.accounts.position;
+    let amount_0 = amount_0_requested.min(position.tokens_owed_0);
+    let amount_1 = amount_1_requested.min(position.tokens_owed_1);
+    position.tokens_owed_0 -= amount_0;
+    position.tokens_owed_1 -= amount_1;
+
+    util::transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_0,
+        &ctx.accounts.recipient_token_account_0,
+        &ctx.accounts.vault_0_mint,
+        &ctx.accounts.token_program_0,
+        ctx.remaining_accounts,
+        amount_0,
+    )?;
+    util::transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_1,
+        &ctx.accounts.recipient_token_account_1,
+        &ctx.accounts.vault_1_mint,
+        &ctx.accounts.token_program_1,
+        ctx.remaining_accounts,
+        amount_1,
+    )?;
+    Ok(())
+}
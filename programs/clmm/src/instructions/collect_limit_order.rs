@@ -0,0 +1,86 @@
+use anchor_lang::{prelude::*, Accounts};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::constants::{LIMIT_ORDER_SEED, TICK_ARRAY_SEED};
+use crate::error::ErrorCode;
+use crate::libraries::{limit_order_math, tick_math};
+use crate::state::{LimitOrderState, PoolState, TickStateArray};
+use crate::util;
+
+#[derive(Accounts)]
+#[instruction(tick_index: i32)]
+pub struct CollectLimitOrder<'info> {
+    pub owner: Signer<'info>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            LIMIT_ORDER_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &tick_index.to_le_bytes(),
+            owner.key().as_ref()],
+        bump,
+    )]
+    pub limit_order: Box<Account<'info, LimitOrderState>>,
+
+    /// Stores the order's tick
+    #[account(
+        seeds = [
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &TickStateArray::get_array_start_index(
+                tick_index, pool_state.load()?.tick_spacing).to_le_bytes(),
+        ],
+        bump
+    )]
+    pub tick_array: AccountLoader<'info, TickStateArray>,
+
+    /// The vault of the token the order buys, token_0 if the order is zero_for_one
+    #[account(
+        mut,
+        constraint = output_vault.key() == if limit_order.zero_for_one {
+            pool_state.load()?.token_vault_0
+        } else {
+            pool_state.load()?.token_vault_1
+        } @ ErrorCode::InvalidInputPoolVault,
+    )]
+    pub output_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::mint = output_vault.mint)]
+    pub recipient_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = output_vault.mint, mint::token_program = output_token_program)]
+    pub output_vault_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub output_token_program: Interface<'info, TokenInterface>,
+
+    // remaining accounts
+    // transfer-hook program and extra accounts of hooked mints
+}
+
+/// Pays out what the order has been filled for since the last collect
+pub fn collect_limit_order_impl<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CollectLimitOrder<'info>>,
+        tick_index: i32,
+    ) -> Result<()> {
+    let tick_state = ctx.accounts.tick_array.load()?.get_tick_state(tick_index)?;
+    let limit_order = &mut ctx.accounts.limit_order;
+    let filled = limit_order.settle_filled(&tick_state);
+    let amount = limit_order_math::get_amount_out_at_price(
+        tick_math::get_sqrt_price_at_tick(tick_index)?,
+        filled,
+        !limit_order.zero_for_one,
+    )
+    .ok_or(ErrorCode::MaxTokenOverflow)?;
+
+    util::transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.output_vault,
+        &ctx.accounts.recipient_token_account,
+        &ctx.accounts.output_vault_mint,
+        &ctx.accounts.output_token_program,
+        ctx.remaining_accounts,
+        amount,
+    )
+}
@@ -0,0 +1,72 @@
+use anchor_lang::{prelude::*, Accounts};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::state::PoolState;
+use crate::error::ErrorCode;
+use crate::util;
+
+#[derive(Accounts)]
+pub struct CollectProtocolFee<'info> {
+    /// Only the admin can collect the protocol fees
+    #[account(address = crate::admin::ID @ ErrorCode::NotApproved)]
+    pub owner: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(mut, constraint = token_vault_0.key() == pool_state.load()?.token_vault_0)]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, constraint = token_vault_1.key() == pool_state.load()?.token_vault_1)]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::mint = token_vault_0.mint)]
+    pub recipient_token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::mint = token_vault_1.mint)]
+    pub recipient_token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = token_vault_0.mint, mint::token_program = token_program_0)]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(address = token_vault_1.mint, mint::token_program = token_program_1)]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub token_program_0: Interface<'info, TokenInterface>,
+    pub token_program_1: Interface<'info, TokenInterface>,
+
+    // remaining accounts
+    // transfer-hook program and extra accounts of hooked mints
+}
+
+/// Pays out up to the requested amounts of the protocol fees accrued by the pool
+pub fn collect_protocol_fee_impl<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CollectProtocolFee<'info>>,
+        amount_0_requested: u64,
+        amount_1_requested: u64,
+    ) -> Result<()> {
+    let (amount_0, amount_1) = {
+        let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+        let amount_0 = u128::from(amount_0_requested).min(pool_state.protocol_fees_0);
+        let amount_1 = u128::from(amount_1_requested).min(pool_state.protocol_fees_1);
+        pool_state.protocol_fees_0 -= amount_0;
+        pool_state.protocol_fees_1 -= amount_1;
+        (amount_0 as u64, amount_1 as u64)
+    };
+
+    util::transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_0,
+        &ctx.accounts.recipient_token_account_0,
+        &ctx.accounts.vault_0_mint,
+        &ctx.accounts.token_program_0,
+        ctx.remaining_accounts,
+        amount_0,
+    )?;
+    util::transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_1,
+        &ctx.accounts.recipient_token_account_1,
+        &ctx.accounts.vault_1_mint,
+        &ctx.accounts.token_program_1,
+        ctx.remaining_accounts,
+        amount_1,
+    )?;
+    Ok(())
+}
@@ -0,0 +1,124 @@
+use anchor_lang::{prelude::*, Accounts};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::constants::{POSITION_SEED, REWARD_NUM, TICK_ARRAY_SEED};
+use crate::state::{self, PoolState, PositionState, TickStateArray};
+use crate::error::ErrorCode;
+use crate::util;
+
+#[derive(Accounts)]
+#[instruction(tick_lower: i32, tick_upper: i32)]
+pub struct CollectRewards<'info> {
+    pub lp: Signer<'info>,
+
+    /// Holding the position NFT is what authorises changes to the position
+    #[account(
+        token::authority = lp,
+        constraint = nft_account.amount == 1 @ ErrorCode::NotPositionNftHolder,
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &tick_lower.to_le_bytes(),
+            &tick_upper.to_le_bytes(),
+            nft_account.mint.as_ref()],
+        bump,
+    )]
+    pub position: Box<Account<'info, PositionState>>,
+
+    /// Stores the position's lower tick
+    #[account(
+        seeds = [
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &TickStateArray::get_array_start_index(
+                tick_lower, pool_state.load()?.tick_spacing).to_le_bytes(),
+        ],
+        bump
+    )]
+    pub tick_array_lower: AccountLoader<'info, TickStateArray>,
+
+    /// Stores the position's upper tick
+    #[account(
+        seeds = [
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &TickStateArray::get_array_start_index(
+                tick_upper, pool_state.load()?.tick_spacing).to_le_bytes(),
+        ],
+        bump
+    )]
+    pub tick_array_upper: AccountLoader<'info, TickStateArray>,
+
+    // remaining accounts
+    // for every initialized reward, in reward index order:
+    //   reward_token_vault, recipient_token_account, reward_token_mint, reward_token_program
+    // then the transfer-hook program and extra accounts of hooked reward mints
+}
+
+/// Pays out all rewards owed to the position
+pub fn collect_rewards_impl<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CollectRewards<'info>>,
+        tick_lower: i32,
+        tick_upper: i32,
+    ) -> Result<()> {
+    // === Settle Rewards Earned Since The Last Update ===
+    let reward_infos = {
+        let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+        pool_state.update_reward_infos(Clock::get()?.unix_timestamp as u64)?;
+        if ctx.accounts.position.liquidity > 0 {
+            let reward_growths_inside_x64 = state::get_reward_growths_inside(
+                &ctx.accounts.tick_array_lower.load()?.get_tick_state(tick_lower)?,
+                &ctx.accounts.tick_array_upper.load()?.get_tick_state(tick_upper)?,
+                tick_lower,
+                tick_upper,
+                pool_state.tick_current,
+                &pool_state.reward_infos,
+            );
+            ctx.accounts.position.update_rewards(reward_growths_inside_x64)?;
+        }
+        pool_state.reward_infos
+    };
+
+    // === Load Reward Accounts ===
+    let mut remaining_accounts = ctx.remaining_accounts;
+    let mut rewards = Vec::with_capacity(REWARD_NUM as usize);
+    for (reward_index, reward_info) in reward_infos.iter().enumerate() {
+        if !reward_info.initialized() {
+            continue;
+        }
+        require_gte!(remaining_accounts.len(), 4, ErrorCode::InvalidRewardInputAccount);
+        let reward_token_vault = InterfaceAccount::<TokenAccount>::try_from(&remaining_accounts[0])?;
+        let recipient_token_account = InterfaceAccount::<TokenAccount>::try_from(&remaining_accounts[1])?;
+        let reward_token_mint = InterfaceAccount::<Mint>::try_from(&remaining_accounts[2])?;
+        let reward_token_program = Interface::<TokenInterface>::try_from(&remaining_accounts[3])?;
+        require_keys_eq!(reward_token_vault.key(), reward_info.token_vault, ErrorCode::InvalidRewardInputAccount);
+        require_keys_eq!(reward_token_mint.key(), reward_info.token_mint, ErrorCode::InvalidRewardInputAccount);
+        require_keys_eq!(recipient_token_account.mint, reward_info.token_mint, ErrorCode::InvalidRewardInputAccount);
+        rewards.push((reward_index, reward_token_vault, recipient_token_account, reward_token_mint, reward_token_program));
+        remaining_accounts = &remaining_accounts[4..];
+    }
+
+    // === Collect ===
+    for (reward_index, reward_token_vault, recipient_token_account, reward_token_mint, reward_token_program) in rewards {
+        let amount = ctx.accounts.position.reward_infos[reward_index].reward_amount_owed;
+        ctx.accounts.position.reward_infos[reward_index].reward_amount_owed = 0;
+        util::transfer_from_pool_vault_to_user(
+            &ctx.accounts.pool_state,
+            &reward_token_vault,
+            &recipient_token_account,
+            &reward_token_mint,
+            &reward_token_program,
+            remaining_accounts,
+            amount,
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use anchor_lang::{prelude::*, Accounts};
+
+use crate::constants::{AMM_CONFIG_SEED, ANCHOR_SIZE};
+use crate::state::AmmConfig;
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+#[instruction(index: u16)]
+pub struct CreateAmmConfig<'info> {
+    /// Only the admin can create fee tiers
+    #[account(mut, address = crate::admin::ID @ ErrorCode::NotApproved)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = ANCHOR_SIZE as usize + AmmConfig::LEN,
+        seeds = [
+            AMM_CONFIG_SEED.as_bytes(),
+            &index.to_le_bytes()
+        ],
+        bump
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_amm_config_impl(
+        ctx: Context<CreateAmmConfig>,
+        index: u16,
+        tick_spacing: u16,
+        trade_fee_rate: u32,
+        protocol_fee_rate: u32,
+        max_dynamic_fee_rate: u32,
+    ) -> Result<()> {
+    ctx.accounts.amm_config.initialize(
+        ctx.bumps.amm_config,
+        index,
+        ctx.accounts.owner.key(),
+        tick_spacing,
+        trade_fee_rate,
+        protocol_fee_rate,
+        max_dynamic_fee_rate,
+    )?;
+    msg!("AmmConfig {} created with tick_spacing {}, trade_fee_rate {}, protocol_fee_rate {}, max_dynamic_fee_rate {}",
+          index,
+          tick_spacing,
+          trade_fee_rate,
+          protocol_fee_rate,
+          max_dynamic_fee_rate);
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use anchor_lang::{prelude::*, Accounts};
+use anchor_spl::token_interface::Mint;
+
+use crate::constants::{ANCHOR_SIZE, SUPPORT_MINT_SEED};
+use crate::state::SupportMintAssociated;
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct CreateSupportMintAssociated<'info> {
+    /// Only the admin can allow mints with unsupported extensions
+    #[account(mut, address = crate::admin::ID @ ErrorCode::NotApproved)]
+    pub owner: Signer<'info>,
+
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = ANCHOR_SIZE as usize + SupportMintAssociated::LEN,
+        seeds = [
+            SUPPORT_MINT_SEED.as_bytes(),
+            token_mint.key().as_ref()
+        ],
+        bump
+    )]
+    pub support_mint_associated: Account<'info, SupportMintAssociated>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_support_mint_associated_impl(ctx: Context<CreateSupportMintAssociated>) -> Result<()> {
+    let support_mint_associated = &mut ctx.accounts.support_mint_associated;
+    support_mint_associated.bump = ctx.bumps.support_mint_associated;
+    support_mint_associated.mint = ctx.accounts.token_mint.key();
+    msg!("Mint {} is allowed in pools", support_mint_associated.mint);
+    Ok(())
+}
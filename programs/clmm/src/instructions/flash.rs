@@ -0,0 +1,157 @@
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke;
+use anchor_lang::{prelude::*, Accounts};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::constants::FEE_RATE_DENOMINATOR_VALUE;
+use crate::error::ErrorCode;
+use crate::libraries::big_num::U128;
+use crate::libraries::fixed_point_64;
+use crate::libraries::full_math::MulDiv;
+use crate::state::PoolState;
+use crate::util;
+
+#[derive(Accounts)]
+pub struct Flash<'info> {
+    pub borrower: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(mut, constraint = token_vault_0.key() == pool_state.load()?.token_vault_0)]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, constraint = token_vault_1.key() == pool_state.load()?.token_vault_1)]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::mint = token_vault_0.mint)]
+    pub recipient_token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::mint = token_vault_1.mint)]
+    pub recipient_token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = token_vault_0.mint, mint::token_program = token_program_0)]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(address = token_vault_1.mint, mint::token_program = token_program_1)]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub token_program_0: Interface<'info, TokenInterface>,
+    pub token_program_1: Interface<'info, TokenInterface>,
+
+    /// CHECK: Invoked with data and the remaining accounts, must pay the loan back into the vaults
+    #[account(executable, constraint = callback_program.key() != crate::ID @ ErrorCode::InvalidFlashCallback)]
+    pub callback_program: UncheckedAccount<'info>,
+
+    // remaining accounts
+    // the accounts of the callback instruction, signer and writable flags are kept.
+    // They are also searched for transfer-hook accounts of hooked mints.
+}
+
+/// Lends amount_0 and amount_1 out of the vaults, invokes callback_program and
+/// requires the vaults to hold the loan plus a fee at the pool's fee_rate afterwards.
+/// Everything paid back on top of the loan is shared by in-range LPs like a swap fee.
+pub fn flash_impl<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, Flash<'info>>,
+    amount_0: u64,
+    amount_1: u64,
+    data: Vec<u8>,
+) -> Result<()> {
+    let (fee_0, fee_1) = {
+        let pool_state = ctx.accounts.pool_state.load()?;
+        require!(pool_state.liquidity > 0, ErrorCode::LiquidityZero);
+        let fee_rate = u64::from(pool_state.fee_rate);
+        let denominator = u64::from(FEE_RATE_DENOMINATOR_VALUE);
+        (
+            amount_0.mul_div_ceil(fee_rate, denominator).ok_or(ErrorCode::MaxTokenOverflow)?,
+            amount_1.mul_div_ceil(fee_rate, denominator).ok_or(ErrorCode::MaxTokenOverflow)?,
+        )
+    };
+    let balance_0_before = ctx.accounts.token_vault_0.amount;
+    let balance_1_before = ctx.accounts.token_vault_1.amount;
+
+    // === Lend ===
+    util::transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_0,
+        &ctx.accounts.recipient_token_account_0,
+        &ctx.accounts.vault_0_mint,
+        &ctx.accounts.token_program_0,
+        ctx.remaining_accounts,
+        amount_0,
+    )?;
+    util::transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_1,
+        &ctx.accounts.recipient_token_account_1,
+        &ctx.accounts.vault_1_mint,
+        &ctx.accounts.token_program_1,
+        ctx.remaining_accounts,
+        amount_1,
+    )?;
+
+    // === Callback ===
+    let callback_instruction = Instruction {
+        program_id: ctx.accounts.callback_program.key(),
+        accounts: ctx
+            .remaining_accounts
+            .iter()
+            .map(|account_info| AccountMeta {
+                pubkey: account_info.key(),
+                is_signer: account_info.is_signer,
+                is_writable: account_info.is_writable,
+            })
+            .collect(),
+        data,
+    };
+    let mut callback_accounts = ctx.remaining_accounts.to_vec();
+    callback_accounts.push(ctx.accounts.callback_program.to_account_info());
+    invoke(&callback_instruction, &callback_accounts)?;
+
+    // === Check Repayment ===
+    ctx.accounts.token_vault_0.reload()?;
+    ctx.accounts.token_vault_1.reload()?;
+    let paid_0 = ctx
+        .accounts
+        .token_vault_0
+        .amount
+        .checked_sub(balance_0_before)
+        .ok_or(ErrorCode::FlashLoanNotRepaid)?;
+    let paid_1 = ctx
+        .accounts
+        .token_vault_1
+        .amount
+        .checked_sub(balance_1_before)
+        .ok_or(ErrorCode::FlashLoanNotRepaid)?;
+    require_gte!(paid_0, fee_0, ErrorCode::FlashLoanNotRepaid);
+    require_gte!(paid_1, fee_1, ErrorCode::FlashLoanNotRepaid);
+
+    // === Credit Fees ===
+    let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+    let liquidity = pool_state.liquidity;
+    let protocol_fee_rate = u64::from(pool_state.protocol_fee_rate);
+    let (lp_fee_0, protocol_fee_0) = split_flash_fee(paid_0, protocol_fee_rate);
+    let (lp_fee_1, protocol_fee_1) = split_flash_fee(paid_1, protocol_fee_rate);
+    pool_state.protocol_fees_0 += u128::from(protocol_fee_0);
+    pool_state.protocol_fees_1 += u128::from(protocol_fee_1);
+    pool_state.fee_growth_global_0_x64 = pool_state
+        .fee_growth_global_0_x64
+        .wrapping_add(fee_growth_delta(lp_fee_0, liquidity));
+    pool_state.fee_growth_global_1_x64 = pool_state
+        .fee_growth_global_1_x64
+        .wrapping_add(fee_growth_delta(lp_fee_1, liquidity));
+
+    msg!("Flash loan of {} and {} repaid with {} and {} in fees", amount_0, amount_1, paid_0, paid_1);
+    Ok(())
+}
+
+/// Returns (lp_fee, protocol_fee)
+fn split_flash_fee(paid: u64, protocol_fee_rate: u64) -> (u64, u64) {
+    let protocol_fee = paid
+        .mul_div_floor(protocol_fee_rate, u64::from(FEE_RATE_DENOMINATOR_VALUE))
+        .unwrap();
+    (paid - protocol_fee, protocol_fee)
+}
+
+fn fee_growth_delta(fee: u64, liquidity: u128) -> u128 {
+    U128::from(fee)
+        .mul_div_floor(U128::from(fixed_point_64::Q64), U128::from(liquidity))
+        .unwrap()
+        .as_u128()
+}
@@ -0,0 +1,23 @@
+use anchor_lang::{prelude::*, Accounts};
+
+use crate::state::{ObservationState, PoolState};
+
+#[derive(Accounts)]
+pub struct GetTwapTick<'info> {
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+}
+
+/// Returns the time-weighted average tick over the last window_seconds.
+/// The window must not reach back past the oldest observation.
+pub fn get_twap_tick_impl(ctx: Context<GetTwapTick>, window_seconds: u32) -> Result<i32> {
+    let pool_state = ctx.accounts.pool_state.load()?;
+    ctx.accounts.observation_state.load()?.get_twap_tick(
+        Clock::get()?.unix_timestamp as u32,
+        window_seconds,
+        pool_state.tick_current,
+        pool_state.liquidity,
+    )
+}
@@ -0,0 +1,150 @@
+use anchor_lang::{prelude::*, Accounts};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::constants::{POSITION_SEED, TICK_ARRAY_SEED};
+use crate::state::{ObservationState, PoolState, PositionState, TickStateArray};
+use crate::error::ErrorCode;
+use crate::util::{self, AccountLoad};
+
+use super::modify_position;
+
+#[derive(Accounts)]
+#[instruction(tick_lower: i32, tick_upper: i32)]
+pub struct IncreaseLiquidity<'info> {
+    pub lp: Signer<'info>,
+
+    /// Holding the position NFT is what authorises changes to the position
+    #[account(
+        token::authority = lp,
+        constraint = nft_account.amount == 1 @ ErrorCode::NotPositionNftHolder,
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    #[account(
+        mut,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &tick_lower.to_le_bytes(),
+            &tick_upper.to_le_bytes(),
+            nft_account.mint.as_ref()],
+        bump,
+    )]
+    pub position: Box<Account<'info, PositionState>>,
+
+    #[account(mut, constraint = token_vault_0.key() == pool_state.load()?.token_vault_0)]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, constraint = token_vault_1.key() == pool_state.load()?.token_vault_1)]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::mint = token_vault_0.mint)]
+    pub token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::mint = token_vault_1.mint)]
+    pub token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = token_vault_0.mint, mint::token_program = token_program_0)]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(address = token_vault_1.mint, mint::token_program = token_program_1)]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Stores the position's lower tick, already created by open_position
+    #[account(
+        mut,
+        seeds = [
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &TickStateArray::get_array_start_index(
+                tick_lower, pool_state.load()?.tick_spacing).to_le_bytes(),
+        ],
+        bump
+    )]
+    pub tick_array_lower: AccountLoader<'info, TickStateArray>,
+
+    /// Stores the position's upper tick, already created by open_position
+    #[account(
+        mut,
+        seeds = [
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &TickStateArray::get_array_start_index(
+                tick_upper, pool_state.load()?.tick_spacing).to_le_bytes(),
+        ],
+        bump
+    )]
+    pub tick_array_upper: AccountLoader<'info, TickStateArray>,
+
+    pub token_program_0: Interface<'info, TokenInterface>,
+    pub token_program_1: Interface<'info, TokenInterface>,
+
+    // remaining accounts
+    // tick_array_bitmap, required when one of the tick arrays gets initialized again
+    // transfer-hook program and extra accounts of hooked mints
+}
+
+pub fn increase_liquidity_impl<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, IncreaseLiquidity<'info>>,
+        tick_lower: i32,
+        tick_upper: i32,
+        liquidity: u128,
+        amount_0_max: u64,
+        amount_1_max: u64,
+    ) -> Result<()> {
+    require!(liquidity > 0, ErrorCode::LiquidityZero);
+
+    let tick_array_lower = AccountLoad::<TickStateArray>::try_from(
+        &ctx.accounts.tick_array_lower.to_account_info())?;
+    let tick_array_upper = AccountLoad::<TickStateArray>::try_from(
+        &ctx.accounts.tick_array_upper.to_account_info())?;
+
+    // === Update Ticks, Position And Pool ===
+    let (amount_0, amount_1) = {
+        let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+        modify_position(
+            liquidity as i128,
+            pool_state,
+            &ctx.accounts.observation_state,
+            &mut ctx.accounts.position,
+            &tick_array_lower,
+            &tick_array_upper,
+            tick_lower,
+            tick_upper,
+            ctx.remaining_accounts,
+        )?
+    };
+
+    // The vaults must receive the full amounts, so the LP pays the transfer fees on top
+    let amount_0 = amount_0
+        .checked_add(util::get_transfer_inverse_fee(&ctx.accounts.vault_0_mint, amount_0)?)
+        .ok_or(ErrorCode::TransferFeeCalculateErr)?;
+    let amount_1 = amount_1
+        .checked_add(util::get_transfer_inverse_fee(&ctx.accounts.vault_1_mint, amount_1)?)
+        .ok_or(ErrorCode::TransferFeeCalculateErr)?;
+    require!(amount_0 <= amount_0_max, ErrorCode::SlippageLimitExceeded);
+    require!(amount_1 <= amount_1_max, ErrorCode::SlippageLimitExceeded);
+
+    // === Transfer Tokens ===
+    util::transfer_from_user_to_pool_vault(
+        &ctx.accounts.lp,
+        &ctx.accounts.token_account_0,
+        &ctx.accounts.token_vault_0,
+        &ctx.accounts.vault_0_mint,
+        &ctx.accounts.token_program_0,
+        ctx.remaining_accounts,
+        amount_0,
+    )?;
+    util::transfer_from_user_to_pool_vault(
+        &ctx.accounts.lp,
+        &ctx.accounts.token_account_1,
+        &ctx.accounts.token_vault_1,
+        &ctx.accounts.vault_1_mint,
+        &ctx.accounts.token_program_1,
+        ctx.remaining_accounts,
+        amount_1,
+    )?;
+    Ok(())
+}
@@ -4,27 +4,35 @@ use anchor_spl::token_interface::TokenAccount;
 use anchor_spl::token_interface::TokenInterface;
 
 use crate::libraries::tick_math;
+use crate::state::AmmConfig;
+use crate::state::ObservationState;
 use crate::state::PoolState;
 use crate::state::TickStateArrayBitMap;
 use crate::constants::ANCHOR_SIZE;
+use crate::constants::OBSERVATION_SEED;
 use crate::constants::POOL_SEED;
 use crate::constants::POOL_VAULT_SEED;
 use crate::constants::SQRT_PRICE_X64_MAX;
 use crate::constants::SQRT_PRICE_X64_MIN;
 use crate::constants::TICK_ARRAY_BITMAP_SEED;
 use crate::error::ErrorCode;
+use crate::util;
 
 #[derive(Accounts)]
 pub struct InitializePool<'info> {
     #[account(mut)]
     pub pool_creator: Signer<'info>,
 
+    /// The fee tier of the pool
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
     #[account(
         init,
         payer = pool_creator,
         space = ANCHOR_SIZE as usize + PoolState::LEN,
         seeds = [
             POOL_SEED.as_bytes(), 
+            amm_config.key().as_ref(),
             token_0.key().as_ref(), 
             token_1.key().as_ref()
         ],
@@ -44,6 +52,18 @@ pub struct InitializePool<'info> {
     )]
     pub tick_array_bitmap: AccountLoader<'info, TickStateArrayBitMap>,
 
+    #[account(
+        init,
+        payer = pool_creator,
+        space = ANCHOR_SIZE as usize + ObservationState::LEN,
+        seeds = [
+            OBSERVATION_SEED.as_bytes(),
+            pool_state.key().as_ref()
+        ],
+        bump
+    )]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
     #[account(
         constraint = token_0.key() < token_1.key(),
         mint::token_program = token_program_0,
@@ -92,25 +112,27 @@ pub struct InitializePool<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn initialize_pool_impl(ctx: Context<InitializePool>, 
-               tick_spacing: u16,sqrt_price_x64: u128,
-               fee_rate: u32, protocol_fee_rate: u32) -> Result<()> {
-    require!(tick_spacing > 0, ErrorCode::TickSpacingZero);
+pub fn initialize_pool_impl(ctx: Context<InitializePool>, sqrt_price_x64: u128) -> Result<()> {
     require!(sqrt_price_x64 <= SQRT_PRICE_X64_MAX && sqrt_price_x64 >= SQRT_PRICE_X64_MIN,
             ErrorCode::SqrtPriceX64);
+    for mint in [&ctx.accounts.token_0, &ctx.accounts.token_1] {
+        if !util::is_support_mint_associated(ctx.remaining_accounts, &mint.key()) {
+            util::check_mint_extensions(mint)?;
+        }
+    }
+
     let pool_state = &mut ctx.accounts.pool_state.load_init()?;
     pool_state.initialize(
         ctx.bumps.pool_state, 
-        tick_spacing, 
-        fee_rate, 
-        protocol_fee_rate, 
+        &ctx.accounts.amm_config,
         sqrt_price_x64, 
         tick_math::get_tick_at_sqrt_price(sqrt_price_x64)?, 
         ctx.accounts.token_vault0.key(), 
         ctx.accounts.token_vault_1.key(), 
         ctx.accounts.token_0.as_ref(), 
         ctx.accounts.token_1.as_ref(),
-        ctx.accounts.tick_array_bitmap.key()
+        ctx.accounts.tick_array_bitmap.key(),
+        ctx.accounts.observation_state.key(),
     )?;
 
     ctx.accounts
@@ -118,8 +140,14 @@ pub fn initialize_pool_impl(ctx: Context<InitializePool>,
        .load_mut()?
        .initialize(pool_state.key());
 
-    msg!("Pool for {} and {} has been created", 
+    ctx.accounts
+       .observation_state
+       .load_init()?
+       .initialize(pool_state.key(), Clock::get()?.unix_timestamp as u32);
+
+    msg!("Pool for {} and {} has been created in fee tier {}", 
           ctx.accounts.token_0.key(), 
-          ctx.accounts.token_1.key());
+          ctx.accounts.token_1.key(),
+          ctx.accounts.amm_config.index);
     Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,111 @@
+use anchor_lang::{prelude::*, Accounts};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::constants::POOL_REWARD_VAULT_SEED;
+use crate::state::{PoolState, RewardInfo};
+use crate::error::ErrorCode;
+use crate::util;
+
+#[derive(Accounts)]
+pub struct InitializeReward<'info> {
+    /// Only the admin can add rewards, and funds the whole emission up front
+    #[account(mut, address = crate::admin::ID @ ErrorCode::NotApproved)]
+    pub owner: Signer<'info>,
+
+    #[account(mut, token::mint = reward_token_mint)]
+    pub funder_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(mint::token_program = reward_token_program)]
+    pub reward_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        seeds = [
+            POOL_REWARD_VAULT_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            reward_token_mint.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        token::mint = reward_token_mint,
+        token::authority = pool_state,
+        token::token_program = reward_token_program,
+    )]
+    pub reward_token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub reward_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+
+    // remaining accounts
+    // SupportMintAssociated account of a reward mint whose extensions the admin allowed
+    // transfer-hook program and extra accounts of a hooked reward mint
+}
+
+/// Takes the first free reward slot of the pool and emits emissions_per_second_x64
+/// reward tokens per second to in-range liquidity between open_time and end_time
+pub fn initialize_reward_impl<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, InitializeReward<'info>>,
+        open_time: u64,
+        end_time: u64,
+        emissions_per_second_x64: u128,
+    ) -> Result<()> {
+    let curr_timestamp = Clock::get()?.unix_timestamp as u64;
+    require!(
+        open_time >= curr_timestamp && end_time > open_time && emissions_per_second_x64 > 0,
+        ErrorCode::InvalidRewardParams
+    );
+    let reward_token_mint = ctx.accounts.reward_token_mint.key();
+    if !util::is_support_mint_associated(ctx.remaining_accounts, &reward_token_mint) {
+        util::check_mint_extensions(&ctx.accounts.reward_token_mint)?;
+    }
+    let reward_amount = RewardInfo::get_reward_amount(emissions_per_second_x64, end_time - open_time)?;
+
+    let reward_index = {
+        let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+        pool_state.update_reward_infos(curr_timestamp)?;
+        let reward_infos = pool_state.reward_infos;
+        require!(
+            !reward_infos.iter().any(|reward_info| reward_info.token_mint == reward_token_mint),
+            ErrorCode::RewardTokenAlreadyInUse
+        );
+        let reward_index = reward_infos
+            .iter()
+            .position(|reward_info| !reward_info.initialized())
+            .ok_or(ErrorCode::FullRewardInfo)?;
+        pool_state.reward_infos[reward_index] = RewardInfo {
+            token_mint: reward_token_mint,
+            token_vault: ctx.accounts.reward_token_vault.key(),
+            open_time,
+            end_time,
+            last_update_time: curr_timestamp,
+            emissions_per_second_x64,
+            reward_growth_global_x64: 0,
+        };
+        reward_index
+    };
+
+    // The vault must receive the full reward amount
+    let transfer_amount = reward_amount
+        .checked_add(util::get_transfer_inverse_fee(&ctx.accounts.reward_token_mint, reward_amount)?)
+        .ok_or(ErrorCode::TransferFeeCalculateErr)?;
+    util::transfer_from_user_to_pool_vault(
+        &ctx.accounts.owner,
+        &ctx.accounts.funder_token_account,
+        &ctx.accounts.reward_token_vault,
+        &ctx.accounts.reward_token_mint,
+        &ctx.accounts.reward_token_program,
+        ctx.remaining_accounts,
+        transfer_amount,
+    )?;
+
+    msg!("Reward {} of {} emits {} between {} and {}",
+          reward_index,
+          ctx.accounts.reward_token_mint.key(),
+          reward_amount,
+          open_time,
+          end_time);
+    Ok(())
+}
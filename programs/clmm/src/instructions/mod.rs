@@ -2,8 +2,62 @@ pub mod initialize;
 
 pub use initialize::*;
 
+pub mod create_amm_config;
+pub use create_amm_config::*;
+
+pub mod create_support_mint_associated;
+pub use create_support_mint_associated::*;
+
 pub mod initialize_pool;
 pub use initialize_pool::*;
 
+pub mod modify_position;
+pub use modify_position::*;
+
 pub mod open_position;
 pub use open_position::*;
+
+pub mod increase_liquidity;
+pub use increase_liquidity::*;
+
+pub mod decrease_liquidity;
+pub use decrease_liquidity::*;
+
+pub mod collect_fees;
+pub use collect_fees::*;
+
+pub mod close_position;
+pub use close_position::*;
+
+pub mod initialize_reward;
+pub use initialize_reward::*;
+
+pub mod set_reward_params;
+pub use set_reward_params::*;
+
+pub mod collect_rewards;
+pub use collect_rewards::*;
+
+pub mod swap;
+pub use swap::*;
+
+pub mod open_limit_order;
+pub use open_limit_order::*;
+
+pub mod collect_limit_order;
+pub use collect_limit_order::*;
+
+pub mod cancel_limit_order;
+pub use cancel_limit_order::*;
+
+pub mod flash;
+pub use flash::*;
+
+pub mod collect_protocol_fee;
+pub use collect_protocol_fee::*;
+
+pub mod snapshot_cumulatives_inside;
+pub use snapshot_cumulatives_inside::*;
+
+pub mod get_twap_tick;
+pub use get_twap_tick::*;
@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+use crate::libraries::liquidity_math;
+use crate::state::{self, ObservationState, PoolState, PositionState, TickStateArray, TickStateArrayBitMap};
+use crate::util::{self, AccountLoad};
+
+/// Applies liquidity_delta to a position: updates both ticks, flips the tick arrays
+/// in the bitmap when they become (un)initialized, settles the fees and rewards the position
+/// has earned so far and adjusts the pool's active liquidity.
+///
+/// Returns the token amounts the LP has to pay in (liquidity_delta > 0) or
+/// is paid out (liquidity_delta < 0).
+///
+/// The bitmap is expected among the remaining accounts whenever a tick array flips.
+#[allow(clippy::too_many_arguments)]
+pub fn modify_position<'info>(
+    liquidity_delta: i128,
+    pool_state: &mut PoolState,
+    observation_state: &AccountLoader<'info, ObservationState>,
+    position: &mut PositionState,
+    tick_array_lower: &AccountLoad<'info, TickStateArray>,
+    tick_array_upper: &AccountLoad<'info, TickStateArray>,
+    tick_lower: i32,
+    tick_upper: i32,
+    remaining_accounts: &[AccountInfo<'info>],
+) -> Result<(u64, u64)> {
+    let tick_spacing = pool_state.tick_spacing;
+
+    let block_timestamp = Clock::get()?.unix_timestamp as u32;
+    pool_state.update_reward_infos(u64::from(block_timestamp))?;
+    let snapshot = observation_state
+        .load()?
+        .snapshot(block_timestamp, pool_state.tick_current, pool_state.liquidity);
+
+    // === Update Tick State ===
+    let lower_flipped = tick_array_lower
+        .load_mut()?
+        .update_tick(pool_state, &snapshot, tick_lower, liquidity_delta, false)?;
+    let upper_flipped = tick_array_upper
+        .load_mut()?
+        .update_tick(pool_state, &snapshot, tick_upper, liquidity_delta, true)?;
+
+    // === Update TickStateArrayBitmap ===
+    if lower_flipped || upper_flipped {
+        let bitmap_info = remaining_accounts
+            .iter()
+            .find(|account_info| account_info.key() == pool_state.tick_array_bitmap)
+            .ok_or(ErrorCode::RemainingAccountMissed)?;
+        util::account_map_mut(
+            bitmap_info,
+            |bitmap: &mut TickStateArrayBitMap| -> Result<()> {
+                if lower_flipped {
+                    bitmap.flip(tick_lower, tick_spacing)?;
+                }
+                if upper_flipped {
+                    bitmap.flip(tick_upper, tick_spacing)?;
+                }
+                Ok(())
+            })??;
+    }
+
+    // === Settle Fees And Rewards Into Position ===
+    let tick_lower_state = tick_array_lower.load()?.get_tick_state(tick_lower)?;
+    let tick_upper_state = tick_array_upper.load()?.get_tick_state(tick_upper)?;
+    position.update_rewards(state::get_reward_growths_inside(
+        &tick_lower_state,
+        &tick_upper_state,
+        tick_lower,
+        tick_upper,
+        pool_state.tick_current,
+        &pool_state.reward_infos,
+    ))?;
+    let (fee_growth_inside_0_x64, fee_growth_inside_1_x64) = state::get_fee_growth_inside(
+        &tick_lower_state,
+        &tick_upper_state,
+        tick_lower,
+        tick_upper,
+        pool_state.tick_current,
+        pool_state.fee_growth_global_0_x64,
+        pool_state.fee_growth_global_1_x64,
+    );
+    position.update(liquidity_delta, fee_growth_inside_0_x64, fee_growth_inside_1_x64)?;
+
+    // === Update PoolState ===
+    if pool_state.tick_current >= tick_lower && pool_state.tick_current < tick_upper {
+        observation_state.load_mut()?.update(
+            block_timestamp,
+            pool_state.tick_current,
+            pool_state.liquidity,
+        );
+        pool_state.liquidity = liquidity_math::add_delta(pool_state.liquidity, liquidity_delta)?;
+    }
+
+    // === Calculate Actual Token Amounts ===
+    liquidity_math::get_delta_amounts_signed(
+        pool_state.tick_current,
+        pool_state.sqrt_price_x64,
+        tick_lower,
+        tick_upper,
+        liquidity_delta,
+    )
+}
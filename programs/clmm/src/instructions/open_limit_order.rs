@@ -0,0 +1,159 @@
+use anchor_lang::{prelude::*, Accounts};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::constants::{ANCHOR_SIZE, LIMIT_ORDER_SEED, TICK_ARRAY_SEED};
+use crate::error::ErrorCode;
+use crate::libraries::tick_math;
+use crate::state::{LimitOrderState, ObservationState, PoolState, TickStateArray, TickStateArrayBitMap};
+use crate::util;
+
+#[derive(Accounts)]
+#[instruction(tick_index: i32, zero_for_one: bool)]
+pub struct OpenLimitOrder<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = ANCHOR_SIZE as usize + LimitOrderState::LEN,
+        seeds = [
+            LIMIT_ORDER_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &tick_index.to_le_bytes(),
+            owner.key().as_ref()],
+        bump,
+    )]
+    pub limit_order: Box<Account<'info, LimitOrderState>>,
+
+    /// CHECK: Account to store data for the order's tick
+    /// Just avoid to compute the PDA on chain.
+    #[account(
+        mut,
+        seeds = [
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &TickStateArray::get_array_start_index(
+                tick_index, pool_state.load()?.tick_spacing).to_le_bytes(),
+        ],
+        bump
+    )]
+    pub tick_array_pda: UncheckedAccount<'info>,
+
+    /// The vault of the token the order sells, token_1 if zero_for_one
+    #[account(
+        mut,
+        constraint = input_vault.key() == if zero_for_one {
+            pool_state.load()?.token_vault_1
+        } else {
+            pool_state.load()?.token_vault_0
+        } @ ErrorCode::InvalidInputPoolVault,
+    )]
+    pub input_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::mint = input_vault.mint)]
+    pub input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = input_vault.mint, mint::token_program = input_token_program)]
+    pub input_vault_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub system_program: Program<'info, System>,
+    pub input_token_program: Interface<'info, TokenInterface>,
+
+    // remaining accounts
+    // tick_array_bitmap, required when the tick array gets initialized
+    // transfer-hook program and extra accounts of hooked mints
+}
+
+/// Places amount of the token the order sells on tick_index. Orders selling token_1
+/// (zero_for_one) need the price above the tick, orders selling token_0 below it.
+pub fn open_limit_order_impl<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, OpenLimitOrder<'info>>,
+        tick_index: i32,
+        zero_for_one: bool,
+        amount: u64,
+    ) -> Result<()> {
+    // === Validation ===
+    require!(amount > 0, ErrorCode::ZeroAmountSpecified);
+    let (tick_spacing, pool_sqrt_price_x64) = {
+        let pool_state = ctx.accounts.pool_state.load()?;
+        (pool_state.tick_spacing, pool_state.sqrt_price_x64)
+    };
+    crate::state::tick_index_check!(tick_index, tick_spacing);
+    let sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(tick_index)?;
+    if zero_for_one {
+        require_gt!(pool_sqrt_price_x64, sqrt_price_x64, ErrorCode::InvalidLimitOrderTick);
+    } else {
+        require_gt!(sqrt_price_x64, pool_sqrt_price_x64, ErrorCode::InvalidLimitOrderTick);
+    }
+
+    // === Load Tick Array ===
+    let tick_array = TickStateArray::get_or_create_tick_array(
+        ctx.accounts.tick_array_pda.to_account_info(),
+        ctx.accounts.owner.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.pool_state,
+        TickStateArray::get_array_start_index(tick_index, tick_spacing),
+        tick_spacing,
+        ctx.bumps.tick_array_pda,
+    )?;
+
+    // === Add The Order To Its Tick ===
+    let flipped = {
+        let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+        let block_timestamp = Clock::get()?.unix_timestamp as u32;
+        pool_state.update_reward_infos(u64::from(block_timestamp))?;
+        let snapshot = ctx
+            .accounts
+            .observation_state
+            .load()?
+            .snapshot(block_timestamp, pool_state.tick_current, pool_state.liquidity);
+        let mut tick_array = tick_array.load_mut()?;
+        let (order, flipped) = tick_array.update_orders(tick_index, |tick_state| {
+            tick_state.add_order(pool_state, &snapshot, tick_index, zero_for_one, amount)
+        })?;
+        ctx.accounts.limit_order.initialize(
+            ctx.accounts.pool_state.key(),
+            ctx.accounts.owner.key(),
+            tick_index,
+            zero_for_one,
+            amount,
+            order,
+            &tick_array.get_tick_state(tick_index)?,
+        );
+        flipped
+    };
+
+    // === Update TickStateArrayBitmap ===
+    if flipped {
+        let bitmap_key = ctx.accounts.pool_state.load()?.tick_array_bitmap;
+        let bitmap_info = ctx
+            .remaining_accounts
+            .iter()
+            .find(|account_info| account_info.key() == bitmap_key)
+            .ok_or(ErrorCode::RemainingAccountMissed)?;
+        util::account_map_mut(
+            bitmap_info,
+            |bitmap: &mut TickStateArrayBitMap| bitmap.flip(tick_index, tick_spacing))??;
+    }
+
+    // === Transfer Tokens ===
+    // The vault must receive the full amount, so the owner pays the transfer fee on top
+    let amount = amount
+        .checked_add(util::get_transfer_inverse_fee(&ctx.accounts.input_vault_mint, amount)?)
+        .ok_or(ErrorCode::TransferFeeCalculateErr)?;
+    util::transfer_from_user_to_pool_vault(
+        &ctx.accounts.owner,
+        &ctx.accounts.input_token_account,
+        &ctx.accounts.input_vault,
+        &ctx.accounts.input_vault_mint,
+        &ctx.accounts.input_token_program,
+        ctx.remaining_accounts,
+        amount,
+    )
+}
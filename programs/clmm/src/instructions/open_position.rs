@@ -1,51 +1,103 @@
 
 use anchor_lang::{prelude::*, Accounts};
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::metadata::{self, mpl_token_metadata::types::DataV2, CreateMetadataAccountsV3, Metadata};
+use anchor_spl::token::{self, spl_token::instruction::AuthorityType, MintTo, SetAuthority, Token};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
-use crate::constants::{ANCHOR_SIZE, POSITION_SEED, TICK_ARRAY_SEED};
-use crate::libraries::liquidity_math;
-use crate::state::{PoolState, PositionState, TickStateArray, TickStateArrayBitMap};
+use crate::constants::{
+    ANCHOR_SIZE, POSITION_NFT_NAME, POSITION_NFT_SYMBOL, POSITION_NFT_URI, POSITION_SEED, TICK_ARRAY_SEED,
+};
+use crate::state::{ObservationState, PoolState, PositionState, TickStateArray};
 use crate::error::ErrorCode;
 use crate::util::{self, AccountLoad};
 
+use super::modify_position;
+
 #[derive(Accounts)]
 #[instruction(tick_lower: i32, tick_upper: i32)]
 pub struct OpenPosition<'info> {
     #[account(mut)]
     pub lp: Signer<'info>,
 
+    /// CHECK: Receives the position NFT, may be any wallet or program
+    pub position_nft_owner: UncheckedAccount<'info>,
+
     #[account(mut)]
     pub pool_state: AccountLoader<'info, PoolState>,
 
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    /// A fresh keypair, the pool PDA is the mint authority until the NFT is minted
+    #[account(
+        init,
+        payer = lp,
+        mint::decimals = 0,
+        mint::authority = pool_state,
+        mint::token_program = token_program,
+    )]
+    pub position_nft_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = lp,
+        associated_token::mint = position_nft_mint,
+        associated_token::authority = position_nft_owner,
+        associated_token::token_program = token_program,
+    )]
+    pub position_nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Created by the token metadata program
+    #[account(
+        mut,
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            position_nft_mint.key().as_ref(),
+        ],
+        seeds::program = metadata_program.key(),
+        bump,
+    )]
+    pub metadata_account: UncheckedAccount<'info>,
+
     #[account(
         init,
         payer = lp,
         space = ANCHOR_SIZE as usize + PositionState::LEN,
         seeds = [
             POSITION_SEED.as_bytes(), 
+            pool_state.key().as_ref(),
             &tick_lower.to_le_bytes(),
             &tick_upper.to_le_bytes(),
-            lp.key().as_ref()],
+            position_nft_mint.key().as_ref()],
         bump,
     )]
     pub position: Box<Account<'info, PositionState>>,
 
     #[account(mut, constraint = token_vault_0.key() == pool_state.load_mut()?.token_vault_0)]
-    pub token_vault_0: Box<Account<'info, TokenAccount>>,
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
     #[account(mut, constraint = token_vault_1.key() == pool_state.load_mut()?.token_vault_1)]
-    pub token_vault_1: Box<Account<'info, TokenAccount>>,
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
     #[account(mut, token::mint = token_vault_0.mint)]
-    pub token_account_0: Box<Account<'info, TokenAccount>>,
+    pub token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
     #[account(mut, token::mint = token_vault_1.mint)]
-    pub token_account_1: Box<Account<'info, TokenAccount>>,
+    pub token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = token_vault_0.mint, mint::token_program = token_program_0)]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(address = token_vault_1.mint, mint::token_program = token_program_1)]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
 
     /// CHECK: Account to store data for the position's lower tick
     /// Just avoid to compute the PDA on chain.
     #[account(
+        mut,
         seeds = [
             TICK_ARRAY_SEED.as_bytes(),
             pool_state.key().as_ref(),
-            &tick_lower.to_le_bytes(),
+            &TickStateArray::get_array_start_index(
+                tick_lower, pool_state.load()?.tick_spacing).to_le_bytes(),
         ],
         bump
     )]
@@ -54,10 +106,12 @@ pub struct OpenPosition<'info> {
     /// CHECK: Account to store data for the position's lower tick
     /// Just avoid to compute the PDA on chain.
     #[account(
+        mut,
         seeds = [
             TICK_ARRAY_SEED.as_bytes(),
             pool_state.key().as_ref(),
-            &tick_upper.to_le_bytes(),
+            &TickStateArray::get_array_start_index(
+                tick_upper, pool_state.load()?.tick_spacing).to_le_bytes(),
         ],
         bump
     )]
@@ -65,8 +119,14 @@ pub struct OpenPosition<'info> {
 
     pub system_program: Program<'info, System>,
     pub token_program: Program<'info, Token>,
+    pub token_program_0: Interface<'info, TokenInterface>,
+    pub token_program_1: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub metadata_program: Program<'info, Metadata>,
+    pub rent: Sysvar<'info, Rent>,
 
-    // remaining account
+    // remaining accounts
+    // transfer-hook program and extra accounts of hooked mints
     // #[account(
     //     seeds = [
     //         TICK_ARRAY_BITMAP_SEED.as_bytes(),
@@ -77,8 +137,8 @@ pub struct OpenPosition<'info> {
     // pub tick_array_bitmap: AccountLoader<'info, TickStateArrayBitmap>,
 }
 
-pub fn open_position_impl(
-        ctx: Context<OpenPosition>,
+pub fn open_position_impl<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, OpenPosition<'info>>,
         tick_lower: i32,
         tick_upper: i32,
         liquidity: u128,
@@ -86,96 +146,134 @@ pub fn open_position_impl(
         amount_1_max: u64,
     ) -> Result<()> {
     // === Validation ===
-    let pool_state = ctx.accounts.pool_state.load()?;
-    let tick_spacing = pool_state.tick_spacing;
+    let tick_spacing = ctx.accounts.pool_state.load()?.tick_spacing;
     crate::state::tick_index_check!(tick_lower, tick_spacing);
     crate::state::tick_index_check!(tick_upper, tick_spacing);
     require_gt!(tick_upper, tick_lower, ErrorCode::InvalidTickIndexOrder);
     require!(liquidity > 0, ErrorCode::LiquidityZero);
 
-    // === Calculate Actual Token Amounts ===
-    let (amount_0, amount_1) = liquidity_math::get_delta_amounts_signed(
-            pool_state.tick_current, 
-            pool_state.sqrt_price_x64, 
-            tick_lower, 
-            tick_upper, liquidity as i128)?;
+    // === Load Tick Arrays ===
+    let lower_bump = ctx.bumps.tick_array_lower_pda;
+    let upper_bump = ctx.bumps.tick_array_upper_pda;
+    let tick_array_lower = ctx.accounts.load_tick_array_lower(tick_lower, tick_spacing, lower_bump)?;
+    let tick_array_upper = ctx.accounts.load_tick_array_upper(tick_upper, tick_spacing, upper_bump)?;
+
+    // === Update Ticks, Position And Pool ===
+    ctx.accounts.position.initialize(
+        ctx.accounts.pool_state.key(),
+        ctx.accounts.position_nft_mint.key(),
+        tick_lower,
+        tick_upper,
+    );
+    let (amount_0, amount_1) = {
+        let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+        modify_position(
+            liquidity as i128,
+            pool_state,
+            &ctx.accounts.observation_state,
+            &mut ctx.accounts.position,
+            &tick_array_lower,
+            &tick_array_upper,
+            tick_lower,
+            tick_upper,
+            ctx.remaining_accounts,
+        )?
+    };
+
+    // The vaults must receive the full amounts, so the LP pays the transfer fees on top
+    let amount_0 = amount_0
+        .checked_add(util::get_transfer_inverse_fee(&ctx.accounts.vault_0_mint, amount_0)?)
+        .ok_or(ErrorCode::TransferFeeCalculateErr)?;
+    let amount_1 = amount_1
+        .checked_add(util::get_transfer_inverse_fee(&ctx.accounts.vault_1_mint, amount_1)?)
+        .ok_or(ErrorCode::TransferFeeCalculateErr)?;
     require!(amount_0 <= amount_0_max, ErrorCode::SlippageLimitExceeded);
     require!(amount_1 <= amount_1_max, ErrorCode::SlippageLimitExceeded);
 
     // === Transfer Tokens ===
-    if amount_0 > 0 {
-        token::transfer(ctx.accounts.transfer_ctx_0(), 0)?;
-    }
-    if amount_1 > 0 {
-        token::transfer(ctx.accounts.transfer_ctx_1(), amount_1)?;
-    }
+    util::transfer_from_user_to_pool_vault(
+        &ctx.accounts.lp,
+        &ctx.accounts.token_account_0,
+        &ctx.accounts.token_vault_0,
+        &ctx.accounts.vault_0_mint,
+        &ctx.accounts.token_program_0,
+        ctx.remaining_accounts,
+        amount_0,
+    )?;
+    util::transfer_from_user_to_pool_vault(
+        &ctx.accounts.lp,
+        &ctx.accounts.token_account_1,
+        &ctx.accounts.token_vault_1,
+        &ctx.accounts.vault_1_mint,
+        &ctx.accounts.token_program_1,
+        ctx.remaining_accounts,
+        amount_1,
+    )?;
 
-    // === Update Tick State ===
-    let lower_bump = ctx.bumps.tick_array_lower_pda;
-    let upper_bump = ctx.bumps.tick_array_upper_pda;
-    let lower_flipped = ctx.accounts
-       .load_tick_array_lower(tick_lower, tick_spacing, lower_bump)?
-       .load_mut()?
-       .update_tick(&pool_state, tick_lower, liquidity as i128, false)?;
-    let upper_flipped = ctx.accounts
-       .load_tick_array_upper(tick_upper, tick_spacing, upper_bump)?
-       .load_mut()?
-       .update_tick(&pool_state, tick_upper, liquidity as i128, true)?;
-
-    // === Update TickStateArrayBitmap ===
-    if lower_flipped || upper_flipped {
-        require!(ctx.remaining_accounts.len() >= 1, ErrorCode::RemainingAccountMissed);
-        require_keys_eq!(ctx.remaining_accounts[0].key(), pool_state.tick_array_bitmap);
-        util::account_map_mut(
-            &ctx.remaining_accounts[0], 
-            |bitmap: &mut TickStateArrayBitMap| -> Result<()> {
-                if lower_flipped {
-                    bitmap.flip(tick_lower, tick_spacing)?;
-                }
-                if upper_flipped {
-                    bitmap.flip(tick_upper, tick_spacing)?;
-                }
-                Ok(())
-            })??;
-    }
-    
-    // === Initialize Position Account ===
-    let position = &mut ctx.accounts.position;
-    position.initialize(
-        liquidity, 
-        pool_state.fee_growth_global_0_x64, 
-        pool_state.fee_growth_global_1_x64);
-
-    // === Update PoolState ===
-    drop(pool_state);
-    {
-        let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
-        if pool_state.tick_current >= tick_lower &&
-           pool_state.tick_current < tick_upper {
-            pool_state.liquidity.checked_add(liquidity)
-                .ok_or(ErrorCode::LiquidityAddValueErr)?;
-        }
-    }
-    Ok(())
+    // === Mint Position NFT ===
+    ctx.accounts.mint_position_nft()
 }
 
 impl<'info> OpenPosition<'info> {
-    pub fn transfer_ctx_0(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
-        let cpi_accounts = Transfer {
-            from: self.token_account_0.to_account_info(),
-            to: self.token_vault_0.to_account_info(),
-            authority: self.lp.to_account_info(),
-        };
-        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
-    }
+    /// Mints the single position token to position_nft_owner, attaches its metadata
+    /// and then drops the mint authority so the supply stays fixed at one
+    pub fn mint_position_nft(&self) -> Result<()> {
+        let pool_state = self.pool_state.load()?;
+        let signer_seeds: &[&[&[u8]]] = &[&pool_state.seeds()];
+
+        token::mint_to(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                MintTo {
+                    mint: self.position_nft_mint.to_account_info(),
+                    to: self.position_nft_account.to_account_info(),
+                    authority: self.pool_state.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            1,
+        )?;
+
+        metadata::create_metadata_accounts_v3(
+            CpiContext::new_with_signer(
+                self.metadata_program.to_account_info(),
+                CreateMetadataAccountsV3 {
+                    metadata: self.metadata_account.to_account_info(),
+                    mint: self.position_nft_mint.to_account_info(),
+                    mint_authority: self.pool_state.to_account_info(),
+                    payer: self.lp.to_account_info(),
+                    update_authority: self.pool_state.to_account_info(),
+                    system_program: self.system_program.to_account_info(),
+                    rent: self.rent.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            DataV2 {
+                name: POSITION_NFT_NAME.to_string(),
+                symbol: POSITION_NFT_SYMBOL.to_string(),
+                uri: POSITION_NFT_URI.to_string(),
+                seller_fee_basis_points: 0,
+                creators: None,
+                collection: None,
+                uses: None,
+            },
+            false,
+            true,
+            None,
+        )?;
 
-    pub fn transfer_ctx_1(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
-        let cpi_accounts = Transfer {
-            from: self.token_account_1.to_account_info(),
-            to: self.token_vault_1.to_account_info(),
-            authority: self.lp.to_account_info(),
-        };
-        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+        token::set_authority(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                SetAuthority {
+                    current_authority: self.pool_state.to_account_info(),
+                    account_or_mint: self.position_nft_mint.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            AuthorityType::MintTokens,
+            None,
+        )
     }
 
     pub fn load_tick_array_lower(&self, tick_lower: i32, tick_spacing: u16, bump: u8) -> Result<AccountLoad<'info, TickStateArray>> {
@@ -0,0 +1,103 @@
+use anchor_lang::{prelude::*, Accounts};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::constants::REWARD_NUM;
+use crate::state::{PoolState, RewardInfo};
+use crate::error::ErrorCode;
+use crate::util;
+
+#[derive(Accounts)]
+#[instruction(reward_index: u8)]
+pub struct SetRewardParams<'info> {
+    /// Only the admin can change rewards, and funds any increase of the remaining emission
+    #[account(address = crate::admin::ID @ ErrorCode::NotApproved)]
+    pub owner: Signer<'info>,
+
+    #[account(mut, token::mint = reward_token_mint)]
+    pub funder_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(address = reward_token_vault.mint, mint::token_program = reward_token_program)]
+    pub reward_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        constraint = (reward_index as usize) < REWARD_NUM as usize @ ErrorCode::InvalidRewardIndex,
+        constraint = reward_token_vault.key() == pool_state.load()?.reward_infos[reward_index as usize].token_vault
+            @ ErrorCode::InvalidRewardInputAccount,
+    )]
+    pub reward_token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub reward_token_program: Interface<'info, TokenInterface>,
+
+    // remaining accounts
+    // transfer-hook program and extra accounts of a hooked reward mint
+}
+
+/// Replaces the emission schedule of a reward from now on. The open_time of a
+/// running reward cannot change, a reward that has not started or has ended can
+/// be rescheduled. Emissions that already accrued are kept.
+pub fn set_reward_params_impl<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SetRewardParams<'info>>,
+        reward_index: u8,
+        emissions_per_second_x64: u128,
+        open_time: u64,
+        end_time: u64,
+    ) -> Result<()> {
+    let curr_timestamp = Clock::get()?.unix_timestamp as u64;
+    let reward_index = reward_index as usize;
+
+    let (remaining_amount_before, remaining_amount_after) = {
+        let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+        pool_state.update_reward_infos(curr_timestamp)?;
+        let mut reward_info = pool_state.reward_infos[reward_index];
+        require!(reward_info.initialized(), ErrorCode::InvalidRewardIndex);
+
+        let running = curr_timestamp >= reward_info.open_time && curr_timestamp < reward_info.end_time;
+        if running {
+            require_eq!(open_time, reward_info.open_time, ErrorCode::InvalidRewardParams);
+        } else {
+            require_gte!(open_time, curr_timestamp, ErrorCode::InvalidRewardParams);
+        }
+        require!(end_time > open_time && end_time > curr_timestamp, ErrorCode::InvalidRewardParams);
+
+        let remaining_amount_before = if curr_timestamp < reward_info.end_time {
+            RewardInfo::get_reward_amount(
+                reward_info.emissions_per_second_x64,
+                reward_info.end_time - curr_timestamp.max(reward_info.open_time),
+            )?
+        } else {
+            0
+        };
+        let remaining_amount_after = RewardInfo::get_reward_amount(
+            emissions_per_second_x64,
+            end_time - curr_timestamp.max(open_time),
+        )?;
+
+        reward_info.emissions_per_second_x64 = emissions_per_second_x64;
+        reward_info.open_time = open_time;
+        reward_info.end_time = end_time;
+        pool_state.reward_infos[reward_index] = reward_info;
+        (remaining_amount_before, remaining_amount_after)
+    };
+
+    // Only an increase of the remaining emission has to be funded
+    if remaining_amount_after > remaining_amount_before {
+        let amount = remaining_amount_after - remaining_amount_before;
+        let transfer_amount = amount
+            .checked_add(util::get_transfer_inverse_fee(&ctx.accounts.reward_token_mint, amount)?)
+            .ok_or(ErrorCode::TransferFeeCalculateErr)?;
+        util::transfer_from_user_to_pool_vault(
+            &ctx.accounts.owner,
+            &ctx.accounts.funder_token_account,
+            &ctx.accounts.reward_token_vault,
+            &ctx.accounts.reward_token_mint,
+            &ctx.accounts.reward_token_program,
+            ctx.remaining_accounts,
+            transfer_amount,
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,100 @@
+use anchor_lang::{prelude::*, Accounts};
+
+use crate::constants::TICK_ARRAY_SEED;
+use crate::state::{ObservationState, PoolState, TickStateArray};
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+#[instruction(tick_lower: i32, tick_upper: i32)]
+pub struct SnapshotCumulativesInside<'info> {
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    /// Stores the range's lower tick
+    #[account(
+        seeds = [
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &TickStateArray::get_array_start_index(
+                tick_lower, pool_state.load()?.tick_spacing).to_le_bytes(),
+        ],
+        bump
+    )]
+    pub tick_array_lower: AccountLoader<'info, TickStateArray>,
+
+    /// Stores the range's upper tick
+    #[account(
+        seeds = [
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &TickStateArray::get_array_start_index(
+                tick_upper, pool_state.load()?.tick_spacing).to_le_bytes(),
+        ],
+        bump
+    )]
+    pub tick_array_upper: AccountLoader<'info, TickStateArray>,
+}
+
+/// Only comparable to another snapshot of the same range taken while a position
+/// existed over the whole period between the two
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct CumulativesInside {
+    pub tick_cumulative_inside: i64,
+    pub seconds_per_liquidity_inside_x64: u128,
+    pub seconds_inside: u32,
+}
+
+/// Returns the tick cumulative, seconds per liquidity and seconds spent inside the range.
+/// Both ticks must be initialized.
+pub fn snapshot_cumulatives_inside_impl(
+        ctx: Context<SnapshotCumulativesInside>,
+        tick_lower: i32,
+        tick_upper: i32,
+    ) -> Result<CumulativesInside> {
+    let lower = ctx.accounts.tick_array_lower.load()?.get_tick_state(tick_lower)?;
+    let upper = ctx.accounts.tick_array_upper.load()?.get_tick_state(tick_upper)?;
+    require!(lower.valid() && upper.valid(), ErrorCode::TickNotInitialized);
+
+    let pool_state = ctx.accounts.pool_state.load()?;
+    let tick_current = pool_state.tick_current;
+    let cumulatives_inside = if tick_current < tick_lower {
+        CumulativesInside {
+            tick_cumulative_inside: lower.tick_cumulative_outside.wrapping_sub(upper.tick_cumulative_outside),
+            seconds_per_liquidity_inside_x64: lower
+                .seconds_per_liquidity_outside_x64
+                .wrapping_sub(upper.seconds_per_liquidity_outside_x64),
+            seconds_inside: lower.seconds_outside.wrapping_sub(upper.seconds_outside),
+        }
+    } else if tick_current < tick_upper {
+        let snapshot = ctx.accounts.observation_state.load()?.snapshot(
+            Clock::get()?.unix_timestamp as u32,
+            tick_current,
+            pool_state.liquidity,
+        );
+        CumulativesInside {
+            tick_cumulative_inside: snapshot
+                .tick_cumulative
+                .wrapping_sub(lower.tick_cumulative_outside)
+                .wrapping_sub(upper.tick_cumulative_outside),
+            seconds_per_liquidity_inside_x64: snapshot
+                .seconds_per_liquidity_cumulative_x64
+                .wrapping_sub(lower.seconds_per_liquidity_outside_x64)
+                .wrapping_sub(upper.seconds_per_liquidity_outside_x64),
+            seconds_inside: snapshot
+                .block_timestamp
+                .wrapping_sub(lower.seconds_outside)
+                .wrapping_sub(upper.seconds_outside),
+        }
+    } else {
+        CumulativesInside {
+            tick_cumulative_inside: upper.tick_cumulative_outside.wrapping_sub(lower.tick_cumulative_outside),
+            seconds_per_liquidity_inside_x64: upper
+                .seconds_per_liquidity_outside_x64
+                .wrapping_sub(lower.seconds_per_liquidity_outside_x64),
+            seconds_inside: upper.seconds_outside.wrapping_sub(lower.seconds_outside),
+        }
+    };
+    Ok(cumulatives_inside)
+}
@@ -0,0 +1,643 @@
+use anchor_lang::{prelude::*, Accounts};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::constants::{FEE_RATE_DENOMINATOR_VALUE, SQRT_PRICE_X64_MAX, SQRT_PRICE_X64_MIN, TICK_MAX, TICK_MIN};
+use crate::error::ErrorCode;
+use crate::libraries::{fixed_point_64, limit_order_math, liquidity_math, swap_math, tick_math};
+use crate::libraries::big_num::U128;
+use crate::libraries::full_math::MulDiv;
+use crate::state::{Observation, ObservationState, PoolState, TickStateArray, TickStateArrayBitMap};
+use crate::util::{self, AccountLoad};
+
+#[derive(Accounts)]
+pub struct Swap<'info> {
+    pub payer: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Writable since filling the last limit orders of a tick array can uninitialize it
+    #[account(mut, address = pool_state.load()?.tick_array_bitmap)]
+    pub tick_array_bitmap: AccountLoader<'info, TickStateArrayBitMap>,
+
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    #[account(mut, token::mint = input_vault.mint)]
+    pub input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::mint = output_vault.mint)]
+    pub output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub input_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub output_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = input_vault.mint, mint::token_program = input_token_program)]
+    pub input_vault_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(address = output_vault.mint, mint::token_program = output_token_program)]
+    pub output_vault_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub input_token_program: Interface<'info, TokenInterface>,
+    pub output_token_program: Interface<'info, TokenInterface>,
+
+    // remaining accounts: the TickStateArrays to walk, in swap direction,
+    // starting with the one holding the current tick.
+    // Arrays that are not initialized in the bitmap may be left out.
+    // They are followed by the transfer-hook program and extra accounts of hooked mints.
+}
+
+/// Accumulated state of the swap while walking the tick arrays
+#[derive(Debug)]
+struct SwapState {
+    /// the amount remaining to be swapped in/out of the input/output asset
+    amount_specified_remaining: u64,
+    /// the amount already swapped out/in of the output/input asset
+    amount_calculated: u64,
+    sqrt_price_x64: u128,
+    tick: i32,
+    /// the global fee growth of the input token
+    fee_growth_global_x64: u128,
+    /// amount of input token paid as protocol fee
+    protocol_fee: u64,
+    /// the current liquidity in range
+    liquidity: u128,
+}
+
+/// With is_base_input, amount is the exact input and other_amount_threshold the minimum output.
+/// Otherwise amount is the exact output and other_amount_threshold the maximum input.
+pub fn swap_impl<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, Swap<'info>>,
+    amount: u64,
+    other_amount_threshold: u64,
+    sqrt_price_limit_x64: u128,
+    is_base_input: bool,
+) -> Result<()> {
+    require!(amount > 0, ErrorCode::ZeroAmountSpecified);
+
+    let zero_for_one = {
+        let pool_state = ctx.accounts.pool_state.load()?;
+        let input_vault = ctx.accounts.input_vault.key();
+        let output_vault = ctx.accounts.output_vault.key();
+        if input_vault == pool_state.token_vault_0 && output_vault == pool_state.token_vault_1 {
+            true
+        } else if input_vault == pool_state.token_vault_1 && output_vault == pool_state.token_vault_0 {
+            false
+        } else {
+            return err!(ErrorCode::InvalidInputPoolVault);
+        }
+    };
+
+    let tick_array_count = ctx
+        .remaining_accounts
+        .iter()
+        .take_while(|account_info| account_info.owner == &crate::ID)
+        .count();
+    let (tick_array_infos, hook_accounts) = ctx.remaining_accounts.split_at(tick_array_count);
+    let mut tick_arrays = Vec::with_capacity(tick_array_count);
+    let mut last_start_idx = None;
+    for account_info in tick_array_infos.iter() {
+        let tick_array = AccountLoad::<TickStateArray>::try_from(account_info)?;
+        let (pool_id, start_idx) = {
+            let tick_array = tick_array.load()?;
+            (tick_array.pool_id, tick_array.tick_start_idx)
+        };
+        require_keys_eq!(pool_id, ctx.accounts.pool_state.key(), ErrorCode::InvalidTickArray);
+        // the walk only moves forward through the arrays, so they must come in swap order
+        if let Some(last_start_idx) = last_start_idx {
+            require!(
+                if zero_for_one { start_idx < last_start_idx } else { start_idx > last_start_idx },
+                ErrorCode::InvalidTickArray
+            );
+        }
+        last_start_idx = Some(start_idx);
+        tick_arrays.push(tick_array);
+    }
+
+    // The pool only swaps what reaches the input vault, and for an exact output
+    // pays out enough for the user to receive amount after the transfer fee
+    let amount_specified = if is_base_input {
+        amount
+            .checked_sub(util::get_transfer_fee(&ctx.accounts.input_vault_mint, amount)?)
+            .ok_or(ErrorCode::TransferFeeCalculateErr)?
+    } else {
+        amount
+            .checked_add(util::get_transfer_inverse_fee(&ctx.accounts.output_vault_mint, amount)?)
+            .ok_or(ErrorCode::TransferFeeCalculateErr)?
+    };
+    require!(amount_specified > 0, ErrorCode::ZeroAmountSpecified);
+
+    let (amount_in, amount_out) = {
+        let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+        let block_timestamp = Clock::get()?.unix_timestamp as u32;
+        let snapshot = {
+            let mut observation_state = ctx.accounts.observation_state.load_mut()?;
+            observation_state.update(block_timestamp, pool_state.tick_current, pool_state.liquidity);
+            observation_state.snapshot(block_timestamp, pool_state.tick_current, pool_state.liquidity)
+        };
+        let mut bitmap = ctx.accounts.tick_array_bitmap.load_mut()?;
+        swap_internal(
+            pool_state,
+            &snapshot,
+            &mut bitmap,
+            &tick_arrays,
+            amount_specified,
+            sqrt_price_limit_x64,
+            zero_for_one,
+            is_base_input,
+        )?
+    };
+
+    // Thresholds apply to what the user actually pays and receives
+    let amount_paid = if is_base_input && amount_in == amount_specified {
+        amount
+    } else {
+        amount_in
+            .checked_add(util::get_transfer_inverse_fee(&ctx.accounts.input_vault_mint, amount_in)?)
+            .ok_or(ErrorCode::TransferFeeCalculateErr)?
+    };
+    let amount_received = amount_out
+        .checked_sub(util::get_transfer_fee(&ctx.accounts.output_vault_mint, amount_out)?)
+        .ok_or(ErrorCode::TransferFeeCalculateErr)?;
+    if is_base_input {
+        require_gte!(amount_received, other_amount_threshold, ErrorCode::TooLittleOutputReceived);
+    } else {
+        require_gte!(other_amount_threshold, amount_paid, ErrorCode::TooMuchInputPaid);
+    }
+
+    util::transfer_from_user_to_pool_vault(
+        &ctx.accounts.payer,
+        &ctx.accounts.input_token_account,
+        &ctx.accounts.input_vault,
+        &ctx.accounts.input_vault_mint,
+        &ctx.accounts.input_token_program,
+        hook_accounts,
+        amount_paid,
+    )?;
+    util::transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.output_vault,
+        &ctx.accounts.output_token_account,
+        &ctx.accounts.output_vault_mint,
+        &ctx.accounts.output_token_program,
+        hook_accounts,
+        amount_out,
+    )?;
+    Ok(())
+}
+
+/// Runs the swap against the pool, crossing initialized ticks on the way,
+/// and returns the (amount_in, amount_out) to settle with the user.
+/// snapshot holds the oracle accumulators as of the start of the swap.
+///
+/// Limit orders resting on a tick are filled at the tick's price, without a fee,
+/// before the tick is crossed. If the swap runs out first, the price stays on
+/// the tick without crossing it.
+#[allow(clippy::too_many_arguments)]
+fn swap_internal(
+    pool_state: &mut PoolState,
+    snapshot: &Observation,
+    bitmap: &mut TickStateArrayBitMap,
+    tick_arrays: &[AccountLoad<TickStateArray>],
+    amount_specified: u64,
+    sqrt_price_limit_x64: u128,
+    zero_for_one: bool,
+    is_base_input: bool,
+) -> Result<(u64, u64)> {
+    let sqrt_price_x64 = pool_state.sqrt_price_x64;
+    if zero_for_one {
+        require!(
+            sqrt_price_limit_x64 < sqrt_price_x64 && sqrt_price_limit_x64 > SQRT_PRICE_X64_MIN,
+            ErrorCode::SqrtPriceLimitOverflow
+        );
+    } else {
+        require!(
+            sqrt_price_limit_x64 > sqrt_price_x64 && sqrt_price_limit_x64 < SQRT_PRICE_X64_MAX,
+            ErrorCode::SqrtPriceLimitOverflow
+        );
+    }
+    pool_state.update_reward_infos(u64::from(snapshot.block_timestamp))?;
+    let reward_growths_global_x64 = pool_state.reward_growths_global();
+    pool_state.update_volatility_reference(u64::from(snapshot.block_timestamp));
+    let tick_spacing = pool_state.tick_spacing;
+    let protocol_fee_rate = pool_state.protocol_fee_rate;
+    let tick_limit = tick_math::get_tick_at_sqrt_price(sqrt_price_limit_x64)?;
+    let mut state = SwapState {
+        amount_specified_remaining: amount_specified,
+        amount_calculated: 0,
+        sqrt_price_x64,
+        tick: pool_state.tick_current,
+        fee_growth_global_x64: if zero_for_one {
+            pool_state.fee_growth_global_0_x64
+        } else {
+            pool_state.fee_growth_global_1_x64
+        },
+        protocol_fee: 0,
+        liquidity: pool_state.liquidity,
+    };
+    let mut array_idx = 0;
+
+    // continue swapping as long as we haven't used the entire input/output and haven't reached the price limit
+    while state.amount_specified_remaining != 0 && state.sqrt_price_x64 != sqrt_price_limit_x64 {
+        let sqrt_price_start_x64 = state.sqrt_price_x64;
+        let (tick_next, initialized) = next_initialized_tick(
+            bitmap,
+            tick_arrays,
+            &mut array_idx,
+            state.tick,
+            tick_spacing,
+            tick_limit,
+            zero_for_one,
+        )?;
+        // ensure that we do not overshoot the min/max tick, as the tick math only covers that range
+        let tick_next = tick_next.clamp(TICK_MIN, TICK_MAX);
+
+        // in dynamic-fee mode the fee follows the volatility seen so far, and a step stops
+        // wherever the fee would change, so the fee holds for every tick it covers
+        let fee_rate = pool_state.update_volatility_and_fee_rate(state.tick);
+        let (tick_next, initialized) = match pool_state.get_fee_rate_bound_tick(state.tick, zero_for_one) {
+            Some(bound_tick) if zero_for_one && bound_tick > tick_next => (bound_tick, false),
+            Some(bound_tick) if !zero_for_one && bound_tick < tick_next => (bound_tick, false),
+            _ => (tick_next, initialized),
+        };
+        let sqrt_price_next_x64 = tick_math::get_sqrt_price_at_tick(tick_next)?;
+        let sqrt_price_target_x64 = if zero_for_one {
+            sqrt_price_next_x64.max(sqrt_price_limit_x64)
+        } else {
+            sqrt_price_next_x64.min(sqrt_price_limit_x64)
+        };
+
+        let step = swap_math::compute_swap_step(
+            state.sqrt_price_x64,
+            sqrt_price_target_x64,
+            state.liquidity,
+            state.amount_specified_remaining,
+            fee_rate,
+            is_base_input,
+        )?;
+        state.sqrt_price_x64 = step.sqrt_price_next_x64;
+        if is_base_input {
+            state.amount_specified_remaining = state
+                .amount_specified_remaining
+                .checked_sub(step.amount_in + step.fee_amount)
+                .unwrap();
+            state.amount_calculated = state
+                .amount_calculated
+                .checked_add(step.amount_out)
+                .ok_or(ErrorCode::MaxTokenOverflow)?;
+        } else {
+            state.amount_specified_remaining = state
+                .amount_specified_remaining
+                .checked_sub(step.amount_out)
+                .unwrap();
+            state.amount_calculated = state
+                .amount_calculated
+                .checked_add(step.amount_in + step.fee_amount)
+                .ok_or(ErrorCode::MaxTokenOverflow)?;
+        }
+
+        // the protocol takes its cut of the step fee before the rest is shared by LPs
+        let mut fee_amount = step.fee_amount;
+        if protocol_fee_rate > 0 {
+            let delta = fee_amount
+                .mul_div_floor(
+                    protocol_fee_rate.into(),
+                    u64::from(FEE_RATE_DENOMINATOR_VALUE),
+                )
+                .unwrap();
+            fee_amount -= delta;
+            state.protocol_fee = state.protocol_fee.checked_add(delta).unwrap();
+        }
+        if state.liquidity > 0 {
+            let fee_growth_global_x64_delta = U128::from(fee_amount)
+                .mul_div_floor(U128::from(fixed_point_64::Q64), U128::from(state.liquidity))
+                .unwrap()
+                .as_u128();
+            state.fee_growth_global_x64 =
+                state.fee_growth_global_x64.wrapping_add(fee_growth_global_x64_delta);
+        }
+
+        // shift tick if we reached the next price
+        if state.sqrt_price_x64 == sqrt_price_next_x64 {
+            // if the tick is initialized, run the tick transition
+            if initialized {
+                let mut tick_array = tick_arrays[array_idx].load_mut()?;
+                let (orders_left, flipped) = tick_array.update_orders(tick_next, |tick_state| {
+                    if !tick_state.has_orders_for(zero_for_one) {
+                        return Ok(false);
+                    }
+                    let (amount_in, amount_out) = limit_order_math::compute_order_fill(
+                        sqrt_price_next_x64,
+                        tick_state.order_remaining,
+                        state.amount_specified_remaining,
+                        zero_for_one,
+                        is_base_input,
+                    )
+                    .ok_or(ErrorCode::MaxTokenOverflow)?;
+                    let (amount_specified, amount_calculated) = if is_base_input {
+                        (amount_in, amount_out)
+                    } else {
+                        (amount_out, amount_in)
+                    };
+                    state.amount_specified_remaining -= amount_specified;
+                    state.amount_calculated = state
+                        .amount_calculated
+                        .checked_add(amount_calculated)
+                        .ok_or(ErrorCode::MaxTokenOverflow)?;
+                    tick_state.fill_orders(amount_out);
+                    Ok(tick_state.has_orders_for(zero_for_one))
+                })?;
+                if flipped {
+                    bitmap.flip(tick_next, tick_spacing)?;
+                }
+                if orders_left {
+                    // the price is on the tick but has not crossed it
+                    state.tick = if zero_for_one { tick_next } else { tick_next - 1 };
+                    break;
+                }
+
+                let offset = TickStateArray::tick_index_to_array_index(
+                    tick_next,
+                    tick_array.tick_start_idx,
+                    tick_spacing,
+                )?;
+                let tick_state = &mut tick_array.tick_states[offset];
+                // a tick that only held the filled orders has been cleared, there is nothing to cross
+                if tick_state.valid() {
+                    let (fee_growth_global_0_x64, fee_growth_global_1_x64) = if zero_for_one {
+                        (state.fee_growth_global_x64, pool_state.fee_growth_global_1_x64)
+                    } else {
+                        (pool_state.fee_growth_global_0_x64, state.fee_growth_global_x64)
+                    };
+                    let mut liquidity_net = tick_state.cross(
+                        fee_growth_global_0_x64,
+                        fee_growth_global_1_x64,
+                        snapshot,
+                        &reward_growths_global_x64,
+                    );
+                    // if we're moving leftward, we interpret liquidity_net as the opposite sign
+                    if zero_for_one {
+                        liquidity_net = -liquidity_net;
+                    }
+                    state.liquidity = liquidity_math::add_delta(state.liquidity, liquidity_net)?;
+                }
+            }
+            state.tick = if zero_for_one { tick_next - 1 } else { tick_next };
+        } else if state.sqrt_price_x64 != sqrt_price_start_x64 {
+            // recompute unless we're on a lower tick boundary (i.e. already transitioned ticks), and haven't moved
+            state.tick = tick_math::get_tick_at_sqrt_price(state.sqrt_price_x64)?;
+        }
+    }
+
+    // the next swap carries over the volatility up to where this one ended
+    pool_state.update_volatility_and_fee_rate(state.tick);
+    pool_state.sqrt_price_x64 = state.sqrt_price_x64;
+    pool_state.tick_current = state.tick;
+    pool_state.liquidity = state.liquidity;
+    if zero_for_one {
+        pool_state.fee_growth_global_0_x64 = state.fee_growth_global_x64;
+        pool_state.protocol_fees_0 += u128::from(state.protocol_fee);
+    } else {
+        pool_state.fee_growth_global_1_x64 = state.fee_growth_global_x64;
+        pool_state.protocol_fees_1 += u128::from(state.protocol_fee);
+    }
+
+    let amount_swapped = amount_specified - state.amount_specified_remaining;
+    if is_base_input {
+        Ok((amount_swapped, state.amount_calculated))
+    } else {
+        Ok((state.amount_calculated, amount_swapped))
+    }
+}
+
+/// Finds the next initialized tick in the swap direction, moving through the
+/// supplied tick arrays. Past the current array only the arrays initialized in
+/// the bitmap are visited, and those missing from tick_arrays must not be.
+/// Once the search passes tick_limit, the range bound is returned as an
+/// uninitialized tick.
+///
+/// On return, array_idx points at the array holding the returned tick.
+fn next_initialized_tick(
+    bitmap: &TickStateArrayBitMap,
+    tick_arrays: &[AccountLoad<TickStateArray>],
+    array_idx: &mut usize,
+    current_tick: i32,
+    tick_spacing: u16,
+    tick_limit: i32,
+    zero_for_one: bool,
+) -> Result<(i32, bool)> {
+    let ticks_in_array = TickStateArray::tick_count(tick_spacing);
+    let current_start_idx = TickStateArray::get_array_start_index(current_tick, tick_spacing);
+    let mut start_idx = current_start_idx;
+    loop {
+        if zero_for_one && start_idx + ticks_in_array <= tick_limit {
+            return Ok((TICK_MIN, false));
+        }
+        if !zero_for_one && start_idx > tick_limit {
+            return Ok((TICK_MAX, false));
+        }
+
+        // skip the arrays that the swap has already left behind
+        while *array_idx < tick_arrays.len() {
+            let array_start_idx = tick_arrays[*array_idx].load()?.tick_start_idx;
+            let passed = if zero_for_one {
+                array_start_idx > start_idx
+            } else {
+                array_start_idx < start_idx
+            };
+            if !passed {
+                break;
+            }
+            *array_idx += 1;
+        }
+
+        let tick_array = tick_arrays
+            .get(*array_idx)
+            .map(|tick_array| tick_array.load())
+            .transpose()?
+            .filter(|tick_array| tick_array.tick_start_idx == start_idx);
+        match tick_array {
+            Some(tick_array) => {
+                let tick = if start_idx == current_start_idx {
+                    tick_array.next_initialized_tick(current_tick, zero_for_one)
+                } else {
+                    tick_array.first_initialized_tick(zero_for_one)
+                };
+                if let Some(tick) = tick {
+                    return Ok((tick, true));
+                }
+            }
+            None => require!(
+                !bitmap.is_initialized(start_idx, tick_spacing),
+                ErrorCode::NotEnoughTickArrayAccount
+            ),
+        }
+
+        // jump straight to the next array with an initialized tick
+        match bitmap.next_initialized_tick_array_start_index(start_idx, tick_spacing, zero_for_one) {
+            Some(next_start_idx) => start_idx = next_start_idx,
+            None => return Ok((if zero_for_one { TICK_MIN } else { TICK_MAX }, false)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod swap_test {
+    use super::*;
+    use crate::constants::{TICK_ARRAY_BITMAP_SIZE, TICK_ARRAY_SIZE};
+    use crate::state::TickState;
+
+    const TICK_SPACING: u16 = 10;
+
+    /// A tick array account of spacing 10, with liquidity at the liquidity offsets and only
+    /// a limit order at the order offsets
+    fn tick_array_account(
+        tick_start_idx: i32,
+        liquidity_offsets: &[usize],
+        order_offsets: &[usize],
+    ) -> AccountInfo<'static> {
+        let mut tick_array = TickStateArray {
+            pool_id: Pubkey::default(),
+            tick_start_idx,
+            tick_valid_cnt: 0,
+            tick_spacing: TICK_SPACING,
+            tick_states: [TickState::default(); TICK_ARRAY_SIZE as usize],
+        };
+        for offset in liquidity_offsets {
+            tick_array.tick_states[*offset].liquidity_gross = 1;
+            tick_array.tick_valid_cnt += 1;
+        }
+        for offset in order_offsets {
+            tick_array.tick_states[*offset].order_shares = 1;
+            tick_array.tick_valid_cnt += 1;
+        }
+        let mut data = TickStateArray::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(&tick_array));
+        AccountInfo::new(
+            Box::leak(Box::new(Pubkey::new_unique())),
+            false,
+            true,
+            Box::leak(Box::new(0)),
+            Box::leak(data.into_boxed_slice()),
+            Box::leak(Box::new(crate::ID)),
+            false,
+            0,
+        )
+    }
+
+    fn tick_array_bitmap(start_indices: &[i32]) -> TickStateArrayBitMap {
+        let mut bitmap = TickStateArrayBitMap {
+            pool_id: Pubkey::default(),
+            bitmap_pos: [0; TICK_ARRAY_BITMAP_SIZE as usize],
+            bitmap_neg: [0; TICK_ARRAY_BITMAP_SIZE as usize],
+        };
+        for start_idx in start_indices {
+            bitmap.flip(*start_idx, TICK_SPACING).unwrap();
+        }
+        bitmap
+    }
+
+    fn walk(
+        bitmap: &TickStateArrayBitMap,
+        accounts: &[AccountInfo<'static>],
+        current_tick: i32,
+        tick_limit: i32,
+        zero_for_one: bool,
+    ) -> Result<(i32, bool)> {
+        let tick_arrays = accounts
+            .iter()
+            .map(AccountLoad::<TickStateArray>::try_from)
+            .collect::<Result<Vec<_>>>()?;
+        let mut array_idx = 0;
+        next_initialized_tick(
+            bitmap,
+            &tick_arrays,
+            &mut array_idx,
+            current_tick,
+            TICK_SPACING,
+            tick_limit,
+            zero_for_one,
+        )
+    }
+
+    #[test]
+    fn finds_the_next_tick_in_the_current_array() {
+        let bitmap = tick_array_bitmap(&[0]);
+        let accounts = [tick_array_account(0, &[10, 50], &[])];
+        assert_eq!(walk(&bitmap, &accounts, 305, TICK_MIN, true).unwrap(), (100, true));
+        assert_eq!(walk(&bitmap, &accounts, 305, TICK_MAX, false).unwrap(), (500, true));
+    }
+
+    #[test]
+    fn jumps_over_uninitialized_arrays_that_were_not_passed() {
+        // the arrays at -600 and 600 are left out, which is fine as their bits are unset
+        let bitmap = tick_array_bitmap(&[-1200, 1200]);
+        let accounts = [
+            tick_array_account(0, &[], &[]),
+            tick_array_account(-1200, &[10, 50], &[]),
+        ];
+        assert_eq!(walk(&bitmap, &accounts, 5, TICK_MIN, true).unwrap(), (-700, true));
+        let accounts = [tick_array_account(1200, &[10, 50], &[])];
+        assert_eq!(walk(&bitmap, &accounts, 5, TICK_MAX, false).unwrap(), (1300, true));
+    }
+
+    #[test]
+    fn runs_to_the_end_of_the_range_without_initialized_arrays() {
+        let bitmap = tick_array_bitmap(&[]);
+        assert_eq!(walk(&bitmap, &[], 5, TICK_MIN, true).unwrap(), (TICK_MIN, false));
+        assert_eq!(walk(&bitmap, &[], 5, TICK_MAX, false).unwrap(), (TICK_MAX, false));
+    }
+
+    #[test]
+    fn arrays_past_the_price_limit_are_not_needed() {
+        let bitmap = tick_array_bitmap(&[-1200, 1200]);
+        assert_eq!(walk(&bitmap, &[], 5, 500, false).unwrap(), (TICK_MAX, false));
+        assert_eq!(walk(&bitmap, &[], 5, -500, true).unwrap(), (TICK_MIN, false));
+    }
+
+    #[test]
+    fn missing_initialized_array_is_an_error() {
+        let bitmap = tick_array_bitmap(&[-600]);
+        let accounts = [tick_array_account(0, &[], &[])];
+        assert_eq!(
+            walk(&bitmap, &accounts, 5, TICK_MIN, true).unwrap_err(),
+            ErrorCode::NotEnoughTickArrayAccount.into()
+        );
+        // the current array is just as required once it has initialized ticks
+        let bitmap = tick_array_bitmap(&[0]);
+        assert_eq!(
+            walk(&bitmap, &[], 5, TICK_MIN, true).unwrap_err(),
+            ErrorCode::NotEnoughTickArrayAccount.into()
+        );
+    }
+
+    #[test]
+    fn out_of_order_arrays_do_not_skip_ticks() {
+        // the array at 0 comes after the one at -600 and is never reached
+        let bitmap = tick_array_bitmap(&[-600, 0]);
+        let accounts = [
+            tick_array_account(-600, &[10], &[]),
+            tick_array_account(0, &[10], &[]),
+        ];
+        assert_eq!(
+            walk(&bitmap, &accounts, 305, TICK_MIN, true).unwrap_err(),
+            ErrorCode::NotEnoughTickArrayAccount.into()
+        );
+    }
+
+    #[test]
+    fn stops_at_ticks_with_only_limit_orders() {
+        let bitmap = tick_array_bitmap(&[0, 600]);
+        let accounts = [
+            tick_array_account(0, &[50], &[30]),
+            tick_array_account(600, &[10], &[]),
+        ];
+        assert_eq!(walk(&bitmap, &accounts, 5, TICK_MAX, false).unwrap(), (300, true));
+        let bitmap = tick_array_bitmap(&[600]);
+        let accounts = [
+            tick_array_account(0, &[], &[]),
+            tick_array_account(600, &[40], &[10]),
+        ];
+        assert_eq!(walk(&bitmap, &accounts, 305, TICK_MAX, false).unwrap(), (700, true));
+    }
+}
@@ -10,18 +10,38 @@ use anchor_lang::prelude::*;
 use instructions::*;
 declare_id!("FAsGDFLK4uPpSuJPJYzXx6iWR3f3w6hvtcXCsVX5maS5");
 
+/// The key allowed to create fee tiers, allow mints, manage rewards and collect protocol fees
+pub mod admin {
+    use anchor_lang::prelude::declare_id;
+    declare_id!("8tPz5be8ULfwUtYLoy5SY1jBZhSr5UeYWC6VMvLbPNVw");
+}
+
 #[program]
 pub mod clmm {
     use super::*;
 
-    pub fn initialize_pool(ctx: Context<InitializePool>, 
-                           tick_spacing: u16,sqrt_price_x64: u128,
-                           fee_ratio: u32, protocol_fee: u32) -> Result<()> {
-        instructions::initialize_pool_impl(ctx, tick_spacing, sqrt_price_x64, fee_ratio, protocol_fee)
+    pub fn create_amm_config(
+        ctx: Context<CreateAmmConfig>,
+        index: u16,
+        tick_spacing: u16,
+        trade_fee_rate: u32,
+        protocol_fee_rate: u32,
+        max_dynamic_fee_rate: u32,
+    ) -> Result<()> {
+        create_amm_config_impl(ctx, index, tick_spacing, trade_fee_rate, protocol_fee_rate, max_dynamic_fee_rate)
+    }
+
+    pub fn create_support_mint_associated(ctx: Context<CreateSupportMintAssociated>) -> Result<()> {
+        create_support_mint_associated_impl(ctx)
     }
 
-    pub fn open_position(
-        ctx: Context<OpenPosition>,
+    /// Remaining accounts: the SupportMintAssociated accounts of mints whose extensions the admin allowed
+    pub fn initialize_pool(ctx: Context<InitializePool>, sqrt_price_x64: u128) -> Result<()> {
+        instructions::initialize_pool_impl(ctx, sqrt_price_x64)
+    }
+
+    pub fn open_position<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, OpenPosition<'info>>,
         tick_lower: i32,
         tick_upper: i32,
         liquidity: u128,
@@ -30,4 +50,139 @@ pub mod clmm {
     ) -> Result<()> {
         open_position_impl(ctx, tick_lower, tick_upper, liquidity, amount_0_max, amount_1_max)
     }
+
+    pub fn increase_liquidity<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, IncreaseLiquidity<'info>>,
+        tick_lower: i32,
+        tick_upper: i32,
+        liquidity: u128,
+        amount_0_max: u64,
+        amount_1_max: u64,
+    ) -> Result<()> {
+        increase_liquidity_impl(ctx, tick_lower, tick_upper, liquidity, amount_0_max, amount_1_max)
+    }
+
+    pub fn decrease_liquidity<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, DecreaseLiquidity<'info>>,
+        tick_lower: i32,
+        tick_upper: i32,
+        liquidity: u128,
+        amount_0_min: u64,
+        amount_1_min: u64,
+    ) -> Result<()> {
+        decrease_liquidity_impl(ctx, tick_lower, tick_upper, liquidity, amount_0_min, amount_1_min)
+    }
+
+    pub fn collect_fees<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CollectFees<'info>>,
+        tick_lower: i32,
+        tick_upper: i32,
+        amount_0_requested: u64,
+        amount_1_requested: u64,
+    ) -> Result<()> {
+        collect_fees_impl(ctx, tick_lower, tick_upper, amount_0_requested, amount_1_requested)
+    }
+
+    pub fn close_position(ctx: Context<ClosePosition>, tick_lower: i32, tick_upper: i32) -> Result<()> {
+        close_position_impl(ctx, tick_lower, tick_upper)
+    }
+
+    /// Remaining accounts: the SupportMintAssociated account of the reward mint if the admin
+    /// allowed its extensions, then the transfer-hook accounts of a hooked reward mint
+    pub fn initialize_reward<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, InitializeReward<'info>>,
+        open_time: u64,
+        end_time: u64,
+        emissions_per_second_x64: u128,
+    ) -> Result<()> {
+        initialize_reward_impl(ctx, open_time, end_time, emissions_per_second_x64)
+    }
+
+    pub fn set_reward_params<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SetRewardParams<'info>>,
+        reward_index: u8,
+        emissions_per_second_x64: u128,
+        open_time: u64,
+        end_time: u64,
+    ) -> Result<()> {
+        set_reward_params_impl(ctx, reward_index, emissions_per_second_x64, open_time, end_time)
+    }
+
+    pub fn collect_rewards<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CollectRewards<'info>>,
+        tick_lower: i32,
+        tick_upper: i32,
+    ) -> Result<()> {
+        collect_rewards_impl(ctx, tick_lower, tick_upper)
+    }
+
+    pub fn swap<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, Swap<'info>>,
+        amount_in: u64,
+        minimum_amount_out: u64,
+        sqrt_price_limit_x64: u128,
+    ) -> Result<()> {
+        swap_impl(ctx, amount_in, minimum_amount_out, sqrt_price_limit_x64, true)
+    }
+
+    pub fn swap_exact_output<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, Swap<'info>>,
+        amount_out: u64,
+        max_amount_in: u64,
+        sqrt_price_limit_x64: u128,
+    ) -> Result<()> {
+        swap_impl(ctx, amount_out, max_amount_in, sqrt_price_limit_x64, false)
+    }
+
+    pub fn open_limit_order<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, OpenLimitOrder<'info>>,
+        tick_index: i32,
+        zero_for_one: bool,
+        amount: u64,
+    ) -> Result<()> {
+        open_limit_order_impl(ctx, tick_index, zero_for_one, amount)
+    }
+
+    pub fn collect_limit_order<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CollectLimitOrder<'info>>,
+        tick_index: i32,
+    ) -> Result<()> {
+        collect_limit_order_impl(ctx, tick_index)
+    }
+
+    pub fn cancel_limit_order<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CancelLimitOrder<'info>>,
+        tick_index: i32,
+    ) -> Result<()> {
+        cancel_limit_order_impl(ctx, tick_index)
+    }
+
+    pub fn flash<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, Flash<'info>>,
+        amount_0: u64,
+        amount_1: u64,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        flash_impl(ctx, amount_0, amount_1, data)
+    }
+
+    pub fn collect_protocol_fee<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CollectProtocolFee<'info>>,
+        amount_0_requested: u64,
+        amount_1_requested: u64,
+    ) -> Result<()> {
+        collect_protocol_fee_impl(ctx, amount_0_requested, amount_1_requested)
+    }
+
+    pub fn snapshot_cumulatives_inside(
+        ctx: Context<SnapshotCumulativesInside>,
+        tick_lower: i32,
+        tick_upper: i32,
+    ) -> Result<CumulativesInside> {
+        snapshot_cumulatives_inside_impl(ctx, tick_lower, tick_upper)
+    }
+
+    pub fn get_twap_tick(ctx: Context<GetTwapTick>, window_seconds: u32) -> Result<i32> {
+        get_twap_tick_impl(ctx, window_seconds)
+    }
 }
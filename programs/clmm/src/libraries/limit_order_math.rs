@@ -0,0 +1,169 @@
+use super::big_num::U128;
+use super::fixed_point_64;
+use super::full_math::MulDiv;
+
+/// Converts amount_in into the other token at the price of sqrt_price_x64, rounding down.
+/// zero_for_one means amount_in is token_0 and the result is token_1.
+///
+/// Returns None if the result does not fit in u64.
+pub fn get_amount_out_at_price(sqrt_price_x64: u128, amount_in: u64, zero_for_one: bool) -> Option<u64> {
+    let sqrt_price_x64 = U128::from(sqrt_price_x64);
+    let q64 = U128::from(fixed_point_64::Q64);
+    let amount_out = if zero_for_one {
+        U128::from(amount_in)
+            .mul_div_floor(sqrt_price_x64, q64)?
+            .mul_div_floor(sqrt_price_x64, q64)?
+    } else {
+        U128::from(amount_in)
+            .mul_div_floor(q64, sqrt_price_x64)?
+            .mul_div_floor(q64, sqrt_price_x64)?
+    };
+    u64::try_from(amount_out.as_u128()).ok()
+}
+
+/// The amount of the input token needed to buy amount_out at the price of
+/// sqrt_price_x64, rounding up. zero_for_one means the input is token_0
+/// and amount_out is token_1.
+///
+/// Returns None if the result does not fit in u64.
+pub fn get_amount_in_at_price(sqrt_price_x64: u128, amount_out: u64, zero_for_one: bool) -> Option<u64> {
+    let sqrt_price_x64 = U128::from(sqrt_price_x64);
+    let q64 = U128::from(fixed_point_64::Q64);
+    let amount_in = if zero_for_one {
+        U128::from(amount_out)
+            .mul_div_ceil(q64, sqrt_price_x64)?
+            .mul_div_ceil(q64, sqrt_price_x64)?
+    } else {
+        U128::from(amount_out)
+            .mul_div_ceil(sqrt_price_x64, q64)?
+            .mul_div_ceil(sqrt_price_x64, q64)?
+    };
+    u64::try_from(amount_in.as_u128()).ok()
+}
+
+/// Computes how much of the limit orders resting on a tick a swap takes,
+/// at the tick's price and without a fee.
+///
+/// Returns (amount_in, amount_out), with amount_out never more than order_remaining.
+/// For exact input swaps amount_in never exceeds amount_remaining; when the
+/// remaining input is too small to buy a single unit nothing is filled.
+///
+/// # Arguments
+///
+/// * `sqrt_price_x64` - The sqrt price of the tick the orders rest on
+/// * `order_remaining` - The unfilled amount of the orders, in the swap's output token
+/// * `amount_remaining` - How much input or output amount is remaining to be swapped in/out
+/// * `zero_for_one` - The direction of the swap
+/// * `is_base_input` - Whether amount_remaining is the input (exact input) or the output
+///   (exact output) of the swap
+///
+pub fn compute_order_fill(
+    sqrt_price_x64: u128,
+    order_remaining: u64,
+    amount_remaining: u64,
+    zero_for_one: bool,
+    is_base_input: bool,
+) -> Option<(u64, u64)> {
+    if is_base_input {
+        let amount_out_max = get_amount_out_at_price(sqrt_price_x64, amount_remaining, zero_for_one)
+            .unwrap_or(u64::MAX);
+        if amount_out_max >= order_remaining {
+            let amount_in = get_amount_in_at_price(sqrt_price_x64, order_remaining, zero_for_one)?
+                .min(amount_remaining);
+            Some((amount_in, order_remaining))
+        } else if amount_out_max == 0 {
+            Some((0, 0))
+        } else {
+            Some((amount_remaining, amount_out_max))
+        }
+    } else {
+        let amount_out = amount_remaining.min(order_remaining);
+        let amount_in = get_amount_in_at_price(sqrt_price_x64, amount_out, zero_for_one)?;
+        Some((amount_in, amount_out))
+    }
+}
+
+#[cfg(test)]
+mod limit_order_math_test {
+    use super::*;
+    use crate::libraries::tick_math;
+
+    #[test]
+    fn price_of_one_converts_one_to_one() {
+        let sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(0).unwrap();
+        assert_eq!(get_amount_out_at_price(sqrt_price_x64, 1_000_000, true), Some(1_000_000));
+        assert_eq!(get_amount_out_at_price(sqrt_price_x64, 1_000_000, false), Some(1_000_000));
+        assert_eq!(get_amount_in_at_price(sqrt_price_x64, 1_000_000, true), Some(1_000_000));
+        assert_eq!(get_amount_in_at_price(sqrt_price_x64, 1_000_000, false), Some(1_000_000));
+    }
+
+    #[test]
+    fn exact_input_fills_whole_order_when_amount_is_sufficient() {
+        let sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(6932).unwrap(); // price ~2
+        let (amount_in, amount_out) =
+            compute_order_fill(sqrt_price_x64, 1_000, 1_000_000, true, true).unwrap();
+        assert_eq!(amount_out, 1_000);
+        assert!((500..=501).contains(&amount_in));
+    }
+
+    #[test]
+    fn exact_input_partially_fills_order_when_amount_is_short() {
+        let sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(6932).unwrap();
+        let (amount_in, amount_out) =
+            compute_order_fill(sqrt_price_x64, 1_000_000, 1_000, false, true).unwrap();
+        assert_eq!(amount_in, 1_000);
+        assert!((499..=500).contains(&amount_out));
+    }
+
+    #[test]
+    fn exact_input_dust_fills_nothing() {
+        let sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(6932).unwrap();
+        assert_eq!(compute_order_fill(sqrt_price_x64, 1_000, 1, false, true), Some((0, 0)));
+    }
+
+    #[test]
+    fn exact_output_never_takes_more_than_the_order() {
+        let sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(-6932).unwrap();
+        let (_, amount_out) =
+            compute_order_fill(sqrt_price_x64, 1_000, 5_000, true, false).unwrap();
+        assert_eq!(amount_out, 1_000);
+    }
+
+    mod fuzz_tests {
+        use super::*;
+        use crate::constants::{SQRT_PRICE_X64_MAX, SQRT_PRICE_X64_MIN};
+        use proptest::prelude::*;
+
+        proptest! {
+            #[test]
+            fn compute_order_fill_test (
+                sqrt_price_x64 in SQRT_PRICE_X64_MIN..SQRT_PRICE_X64_MAX,
+                order_remaining in 1..u64::MAX,
+                amount_remaining in 1..u64::MAX,
+                zero_for_one in proptest::bool::ANY,
+                is_base_input in proptest::bool::ANY,
+            ) {
+                let result = compute_order_fill(
+                    sqrt_price_x64,
+                    order_remaining,
+                    amount_remaining,
+                    zero_for_one,
+                    is_base_input,
+                );
+                // results that do not fit in u64 are rejected by the callers
+                if let Some((amount_in, amount_out)) = result {
+                    assert!(amount_out <= order_remaining);
+                    if is_base_input {
+                        assert!(amount_in <= amount_remaining);
+                    } else {
+                        assert!(amount_out <= amount_remaining);
+                    }
+                    // paying the makers out at the same price never takes more than the swap paid in
+                    if let Some(owed) = get_amount_out_at_price(sqrt_price_x64, amount_out, !zero_for_one) {
+                        assert!(owed <= amount_in);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -17,10 +17,10 @@ use anchor_lang::prelude::*;
 pub fn add_delta(x: u128, y: i128) -> Result<u128> {
     let z: u128;
     if y < 0 {
-        z = x - u128::try_from(-y).unwrap();
+        z = x.checked_sub(y.unsigned_abs()).ok_or(ErrorCode::LiquiditySubValueErr)?;
         require_gt!(x, z, ErrorCode::LiquiditySubValueErr);
     } else {
-        z = x + u128::try_from(y).unwrap();
+        z = x.checked_add(y.unsigned_abs()).ok_or(ErrorCode::LiquidityAddValueErr)?;
         require_gte!(z, x, ErrorCode::LiquidityAddValueErr);
     }
 
@@ -314,3 +314,21 @@ pub fn get_delta_amounts_signed(
     }
     Ok((amount_0, amount_1))
 }
+
+#[cfg(test)]
+mod liquidity_math_test {
+    use super::*;
+
+    #[test]
+    fn add_delta_adds_and_removes_liquidity() {
+        assert_eq!(add_delta(10, 5).unwrap(), 15);
+        assert_eq!(add_delta(10, -4).unwrap(), 6);
+        assert_eq!(add_delta(10, -10).unwrap(), 0);
+    }
+
+    #[test]
+    fn add_delta_rejects_underflow_and_overflow() {
+        assert!(add_delta(10, -11).is_err());
+        assert!(add_delta(u128::MAX, 1).is_err());
+    }
+}
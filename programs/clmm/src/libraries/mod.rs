@@ -3,4 +3,8 @@ pub mod tick_math;
 pub mod fixed_point_64;
 pub mod liquidity_math;
 pub mod full_math;
-pub mod unsafe_math;
\ No newline at end of file
+pub mod unsafe_math;
+pub mod sqrt_price_math;
+pub mod swap_math;
+pub mod limit_order_math;
+pub mod tick_array_bitmap_math;
\ No newline at end of file
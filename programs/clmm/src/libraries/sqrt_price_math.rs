@@ -0,0 +1,293 @@
+use super::big_num::U256;
+use super::fixed_point_64;
+use super::full_math::MulDiv;
+use super::unsafe_math::UnsafeMathTrait;
+use crate::error::ErrorCode;
+use anchor_lang::prelude::*;
+
+/// Gets the next sqrt price √P' given a delta of token_0
+///
+/// Always round up because
+/// 1. In the exact output case, token 0 supply decreases leading to price increase.
+///    Move price up so that exact output is met.
+/// 2. In the exact input case, token 0 supply increases leading to price decrease.
+///    Do not round down to minimize price impact. We only need to meet input
+///    change and not guarantee exact output.
+///
+/// Use function for exact input or exact output swaps for token 0
+///
+/// # Formula
+///
+/// * `√P' = √P * L / (L + Δx * √P)`
+///
+/// # Proof
+///
+/// For constant y,
+/// √P * L = y
+/// √P' * L' = √P * L
+/// √P' = √P * L / L'
+/// √P' = √P * L / (L + Δx*√P)
+///
+/// # Arguments
+///
+/// * `sqrt_price_x64` - The starting price `√P`, i.e., before accounting for the token_1 delta,
+///   where P is `token_1_supply / token_0_supply`
+/// * `liquidity` - The amount of usable liquidity L
+/// * `amount` - Delta of token 0 (Δx) to add or remove from virtual reserves
+/// * `add` - Whether to add or remove the amount of token_0
+///
+pub fn get_next_sqrt_price_from_amount_0_rounding_up(
+    sqrt_price_x64: u128,
+    liquidity: u128,
+    amount: u64,
+    add: bool,
+) -> Result<u128> {
+    // we short circuit amount == 0 because the result is otherwise not
+    // guaranteed to equal the input price
+    if amount == 0 {
+        return Ok(sqrt_price_x64);
+    };
+    let numerator_1 = U256::from(liquidity) << fixed_point_64::RESOLUTION;
+    // Δx * √P cannot overflow 256 bits since it is at most 64 + 128 bits wide
+    let product = U256::from(amount) * U256::from(sqrt_price_x64);
+
+    let denominator = if add {
+        numerator_1 + product
+    } else {
+        // if the product overflows, we know the denominator underflows
+        // in addition, we must check that the denominator does not underflow
+        require_gt!(numerator_1, product, ErrorCode::LiquidityInsufficient);
+        numerator_1 - product
+    };
+    let result = numerator_1
+        .mul_div_ceil(U256::from(sqrt_price_x64), denominator)
+        .ok_or(ErrorCode::SqrtPriceX64)?;
+    require!(result <= U256::from(u128::MAX), ErrorCode::SqrtPriceX64);
+    Ok(result.as_u128())
+}
+
+/// Gets the next sqrt price given a delta of token_1
+///
+/// Always round down because
+/// 1. In the exact output case, token 1 supply decreases leading to price decrease.
+///    Move price down by rounding down so that exact output of token 0 is met.
+/// 2. In the exact input case, token 1 supply increases leading to price increase.
+///    Do not round down to minimize price impact. We only need to meet input
+///    change and not gurantee exact output for token 0.
+///
+/// # Formula
+///
+/// * `√P' = √P + Δy / L`
+///
+/// # Arguments
+///
+/// * `sqrt_price_x64` - The starting price `√P`, i.e., before accounting for the token_1 delta
+/// * `liquidity` - The amount of usable liquidity L
+/// * `amount` - Delta of token 1 (Δy) to add or remove from virtual reserves
+/// * `add` - Whether to add or remove the amount of token_1
+///
+pub fn get_next_sqrt_price_from_amount_1_rounding_down(
+    sqrt_price_x64: u128,
+    liquidity: u128,
+    amount: u64,
+    add: bool,
+) -> Result<u128> {
+    if add {
+        let quotient = (U256::from(amount) << fixed_point_64::RESOLUTION) / U256::from(liquidity);
+        let result = U256::from(sqrt_price_x64) + quotient;
+        require!(result <= U256::from(u128::MAX), ErrorCode::SqrtPriceX64);
+        Ok(result.as_u128())
+    } else {
+        let quotient = U256::div_rounding_up(
+            U256::from(amount) << fixed_point_64::RESOLUTION,
+            U256::from(liquidity),
+        );
+        require!(U256::from(sqrt_price_x64) > quotient, ErrorCode::LiquidityInsufficient);
+        Ok(sqrt_price_x64 - quotient.as_u128())
+    }
+}
+
+/// Gets the next sqrt price given an input amount of token_0 or token_1
+/// Throws if price or liquidity are 0, or if the next price is out of bounds
+///
+/// # Arguments
+///
+/// * `sqrt_price_x64` - The starting price `√P`, i.e., before accounting for the input amount
+/// * `liquidity` - The amount of usable liquidity
+/// * `amount_in` - How much of token_0, or token_1, is being swapped in
+/// * `zero_for_one` - Whether the amount in is token_0 or token_1
+///
+pub fn get_next_sqrt_price_from_input(
+    sqrt_price_x64: u128,
+    liquidity: u128,
+    amount_in: u64,
+    zero_for_one: bool,
+) -> Result<u128> {
+    require!(sqrt_price_x64 > 0, ErrorCode::SqrtPriceX64);
+    require!(liquidity > 0, ErrorCode::LiquidityInsufficient);
+
+    // round to make sure that we don't pass the target price
+    if zero_for_one {
+        get_next_sqrt_price_from_amount_0_rounding_up(sqrt_price_x64, liquidity, amount_in, true)
+    } else {
+        get_next_sqrt_price_from_amount_1_rounding_down(sqrt_price_x64, liquidity, amount_in, true)
+    }
+}
+
+/// Gets the next sqrt price given an output amount of token0 or token1
+///
+/// Throws if price or liquidity are 0 or the next price is out of bounds
+///
+/// # Arguments
+///
+/// * `sqrt_price_x64` - The starting price `√P`, i.e., before accounting for the output amount
+/// * `liquidity` - The amount of usable liquidity
+/// * `amount_out` - How much of token_0, or token_1, is being swapped out
+/// * `zero_for_one` - Whether the amount out is token_0 or token_1
+///
+pub fn get_next_sqrt_price_from_output(
+    sqrt_price_x64: u128,
+    liquidity: u128,
+    amount_out: u64,
+    zero_for_one: bool,
+) -> Result<u128> {
+    require!(sqrt_price_x64 > 0, ErrorCode::SqrtPriceX64);
+    require!(liquidity > 0, ErrorCode::LiquidityInsufficient);
+
+    // round to make sure that we pass the target price
+    if zero_for_one {
+        get_next_sqrt_price_from_amount_1_rounding_down(sqrt_price_x64, liquidity, amount_out, false)
+    } else {
+        get_next_sqrt_price_from_amount_0_rounding_up(sqrt_price_x64, liquidity, amount_out, false)
+    }
+}
+
+#[cfg(test)]
+mod sqrt_price_math_test {
+    use super::*;
+    use crate::libraries::tick_math;
+
+    #[test]
+    fn zero_amount_keeps_price() {
+        let sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(0).unwrap();
+        assert_eq!(
+            get_next_sqrt_price_from_input(sqrt_price_x64, 1_000_000, 0, true).unwrap(),
+            sqrt_price_x64
+        );
+        assert_eq!(
+            get_next_sqrt_price_from_input(sqrt_price_x64, 1_000_000, 0, false).unwrap(),
+            sqrt_price_x64
+        );
+    }
+
+    #[test]
+    fn input_moves_price_in_swap_direction() {
+        let sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(0).unwrap();
+        let liquidity = 1_000_000_000u128;
+        let down = get_next_sqrt_price_from_input(sqrt_price_x64, liquidity, 1_000, true).unwrap();
+        let up = get_next_sqrt_price_from_input(sqrt_price_x64, liquidity, 1_000, false).unwrap();
+        assert!(down < sqrt_price_x64);
+        assert!(up > sqrt_price_x64);
+    }
+
+    #[test]
+    fn output_moves_price_in_swap_direction() {
+        let sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(0).unwrap();
+        let liquidity = 1_000_000_000u128;
+        let down = get_next_sqrt_price_from_output(sqrt_price_x64, liquidity, 1_000, true).unwrap();
+        let up = get_next_sqrt_price_from_output(sqrt_price_x64, liquidity, 1_000, false).unwrap();
+        assert!(down < sqrt_price_x64);
+        assert!(up > sqrt_price_x64);
+    }
+
+    #[test]
+    fn output_larger_than_reserves_is_rejected() {
+        let sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(0).unwrap();
+        // at price 1, the virtual reserves of both tokens equal the liquidity
+        assert!(get_next_sqrt_price_from_output(sqrt_price_x64, 1_000, 1_000, true).is_err());
+        assert!(get_next_sqrt_price_from_output(sqrt_price_x64, 1_000, 1_000, false).is_err());
+    }
+
+    #[test]
+    fn zero_liquidity_is_rejected() {
+        let sqrt_price_x64 = tick_math::get_sqrt_price_at_tick(0).unwrap();
+        assert!(get_next_sqrt_price_from_input(sqrt_price_x64, 0, 1, true).is_err());
+    }
+
+    mod fuzz_tests {
+        use super::*;
+        use crate::constants::{SQRT_PRICE_X64_MAX, SQRT_PRICE_X64_MIN};
+        use crate::libraries::liquidity_math;
+        use proptest::prelude::*;
+
+        proptest! {
+            #[test]
+            fn get_next_sqrt_price_from_input_test (
+                sqrt_price_x64 in SQRT_PRICE_X64_MIN..SQRT_PRICE_X64_MAX,
+                liquidity in 1..u64::MAX as u128,
+                amount_in in 1..u64::MAX,
+                zero_for_one in proptest::bool::ANY,
+            ) {
+                let result = get_next_sqrt_price_from_input(sqrt_price_x64, liquidity, amount_in, zero_for_one);
+                prop_assume!(result.is_ok());
+                let next_sqrt_price_x64 = result.unwrap();
+                prop_assume!((SQRT_PRICE_X64_MIN..=SQRT_PRICE_X64_MAX).contains(&next_sqrt_price_x64));
+
+                // the price moves against the input token and never further than the input pays for
+                if zero_for_one {
+                    assert!(next_sqrt_price_x64 <= sqrt_price_x64);
+                    let amount_0 = liquidity_math::get_delta_amount_0_unsigned(
+                        next_sqrt_price_x64, sqrt_price_x64, liquidity, true).unwrap();
+                    assert!(amount_0 <= amount_in);
+                } else {
+                    assert!(next_sqrt_price_x64 >= sqrt_price_x64);
+                    let amount_1 = liquidity_math::get_delta_amount_1_unsigned(
+                        sqrt_price_x64, next_sqrt_price_x64, liquidity, true).unwrap();
+                    assert!(amount_1 <= amount_in);
+                }
+            }
+
+            #[test]
+            fn get_next_sqrt_price_from_output_test (
+                sqrt_price_x64 in SQRT_PRICE_X64_MIN..SQRT_PRICE_X64_MAX,
+                liquidity in 1..u64::MAX as u128,
+                amount_out in 1..u64::MAX,
+                zero_for_one in proptest::bool::ANY,
+            ) {
+                let result = get_next_sqrt_price_from_output(sqrt_price_x64, liquidity, amount_out, zero_for_one);
+                prop_assume!(result.is_ok());
+                let next_sqrt_price_x64 = result.unwrap();
+                prop_assume!((SQRT_PRICE_X64_MIN..=SQRT_PRICE_X64_MAX).contains(&next_sqrt_price_x64));
+
+                // the price moves far enough to release at least the requested output
+                if zero_for_one {
+                    assert!(next_sqrt_price_x64 < sqrt_price_x64);
+                    let amount_1 = liquidity_math::get_delta_amount_1_unsigned(
+                        next_sqrt_price_x64, sqrt_price_x64, liquidity, false);
+                    prop_assume!(amount_1.is_ok());
+                    assert!(amount_1.unwrap() >= amount_out);
+                } else {
+                    assert!(next_sqrt_price_x64 > sqrt_price_x64);
+                    let amount_0 = liquidity_math::get_delta_amount_0_unsigned(
+                        sqrt_price_x64, next_sqrt_price_x64, liquidity, false);
+                    prop_assume!(amount_0.is_ok());
+                    assert!(amount_0.unwrap() >= amount_out);
+                }
+            }
+
+            #[test]
+            fn get_next_sqrt_price_from_amount_1_add_then_remove_test (
+                sqrt_price_x64 in SQRT_PRICE_X64_MIN..SQRT_PRICE_X64_MAX,
+                liquidity in 1..u64::MAX as u128,
+                amount in 0..u64::MAX,
+            ) {
+                let up = get_next_sqrt_price_from_amount_1_rounding_down(sqrt_price_x64, liquidity, amount, true);
+                prop_assume!(up.is_ok());
+                let back = get_next_sqrt_price_from_amount_1_rounding_down(up.unwrap(), liquidity, amount, false);
+                prop_assume!(back.is_ok());
+                // rounding always favours the pool, so the round trip never ends above the start
+                assert!(back.unwrap() <= sqrt_price_x64);
+            }
+        }
+    }
+}
@@ -0,0 +1,364 @@
+use super::full_math::MulDiv;
+use super::liquidity_math;
+use super::sqrt_price_math;
+use crate::constants::FEE_RATE_DENOMINATOR_VALUE;
+use anchor_lang::prelude::*;
+
+/// Result of a swap step
+#[derive(Default, Debug)]
+pub struct SwapStep {
+    /// The price after swapping the amount in/out, not to exceed the price target
+    pub sqrt_price_next_x64: u128,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee_amount: u64,
+}
+
+/// Computes the result of swapping some amount in, or amount out, given the parameters of the swap
+///
+/// For exact input swaps the fee, plus the amount in, will never exceed the amount remaining
+///
+/// # Arguments
+///
+/// * `sqrt_price_current_x64` - The current sqrt price of the pool
+/// * `sqrt_price_target_x64` - The price that cannot be exceeded, from which the direction
+///   of the swap is inferred
+/// * `liquidity` - The usable liquidity
+/// * `amount_remaining` - How much input or output amount is remaining to be swapped in/out
+/// * `fee_rate` - The fee taken from the input amount, in hundredths of a bip
+///   (over `FEE_RATE_DENOMINATOR_VALUE`)
+/// * `is_base_input` - Whether amount_remaining is the input (exact input) or the output
+///   (exact output) of the swap
+///
+pub fn compute_swap_step(
+    sqrt_price_current_x64: u128,
+    sqrt_price_target_x64: u128,
+    liquidity: u128,
+    amount_remaining: u64,
+    fee_rate: u32,
+    is_base_input: bool,
+) -> Result<SwapStep> {
+    let zero_for_one = sqrt_price_current_x64 >= sqrt_price_target_x64;
+    let mut swap_step = SwapStep::default();
+
+    // None means the amount needed to reach the target does not fit in u64,
+    // so the remaining amount can never get there
+    let amount_to_target = if is_base_input {
+        let amount_remaining_less_fee = amount_remaining
+            .mul_div_floor(
+                (FEE_RATE_DENOMINATOR_VALUE - fee_rate).into(),
+                u64::from(FEE_RATE_DENOMINATOR_VALUE),
+            )
+            .unwrap();
+        let amount_in_to_target = calculate_amount_in_range(
+            sqrt_price_current_x64,
+            sqrt_price_target_x64,
+            liquidity,
+            zero_for_one,
+        );
+        swap_step.sqrt_price_next_x64 = match amount_in_to_target {
+            Some(amount_in) if amount_remaining_less_fee >= amount_in => sqrt_price_target_x64,
+            _ => sqrt_price_math::get_next_sqrt_price_from_input(
+                sqrt_price_current_x64,
+                liquidity,
+                amount_remaining_less_fee,
+                zero_for_one,
+            )?,
+        };
+        amount_in_to_target
+    } else {
+        let amount_out_to_target = calculate_amount_out_in_range(
+            sqrt_price_current_x64,
+            sqrt_price_target_x64,
+            liquidity,
+            zero_for_one,
+        );
+        swap_step.sqrt_price_next_x64 = match amount_out_to_target {
+            Some(amount_out) if amount_remaining >= amount_out => sqrt_price_target_x64,
+            _ => sqrt_price_math::get_next_sqrt_price_from_output(
+                sqrt_price_current_x64,
+                liquidity,
+                amount_remaining,
+                zero_for_one,
+            )?,
+        };
+        amount_out_to_target
+    };
+
+    // whether we reached the target price
+    let max = sqrt_price_target_x64 == swap_step.sqrt_price_next_x64;
+
+    // get the input / output amounts, reusing the precomputed amount when the target was reached
+    if zero_for_one {
+        swap_step.amount_in = match amount_to_target {
+            Some(amount_in) if max && is_base_input => amount_in,
+            _ => liquidity_math::get_delta_amount_0_unsigned(
+                swap_step.sqrt_price_next_x64,
+                sqrt_price_current_x64,
+                liquidity,
+                true,
+            )?,
+        };
+        swap_step.amount_out = match amount_to_target {
+            Some(amount_out) if max && !is_base_input => amount_out,
+            _ => liquidity_math::get_delta_amount_1_unsigned(
+                swap_step.sqrt_price_next_x64,
+                sqrt_price_current_x64,
+                liquidity,
+                false,
+            )?,
+        };
+    } else {
+        swap_step.amount_in = match amount_to_target {
+            Some(amount_in) if max && is_base_input => amount_in,
+            _ => liquidity_math::get_delta_amount_1_unsigned(
+                sqrt_price_current_x64,
+                swap_step.sqrt_price_next_x64,
+                liquidity,
+                true,
+            )?,
+        };
+        swap_step.amount_out = match amount_to_target {
+            Some(amount_out) if max && !is_base_input => amount_out,
+            _ => liquidity_math::get_delta_amount_0_unsigned(
+                sqrt_price_current_x64,
+                swap_step.sqrt_price_next_x64,
+                liquidity,
+                false,
+            )?,
+        };
+    }
+
+    // cap the output amount to not exceed the remaining output amount
+    if !is_base_input && swap_step.amount_out > amount_remaining {
+        swap_step.amount_out = amount_remaining;
+    }
+
+    swap_step.fee_amount = if is_base_input && !max {
+        // we didn't reach the target, so take the remainder of the maximum input as fee
+        amount_remaining - swap_step.amount_in
+    } else {
+        // round the fee up, so the pool never undercharges the input
+        swap_step
+            .amount_in
+            .mul_div_ceil(
+                fee_rate.into(),
+                (FEE_RATE_DENOMINATOR_VALUE - fee_rate).into(),
+            )
+            .unwrap()
+    };
+
+    Ok(swap_step)
+}
+
+/// Pre-calculate the input amount required to move the price from current to target.
+/// Returns None if the amount overflows u64.
+fn calculate_amount_in_range(
+    sqrt_price_current_x64: u128,
+    sqrt_price_target_x64: u128,
+    liquidity: u128,
+    zero_for_one: bool,
+) -> Option<u64> {
+    if zero_for_one {
+        liquidity_math::get_delta_amount_0_unsigned(
+            sqrt_price_target_x64,
+            sqrt_price_current_x64,
+            liquidity,
+            true,
+        )
+        .ok()
+    } else {
+        liquidity_math::get_delta_amount_1_unsigned(
+            sqrt_price_current_x64,
+            sqrt_price_target_x64,
+            liquidity,
+            true,
+        )
+        .ok()
+    }
+}
+
+/// Pre-calculate the output amount released by moving the price from current to target.
+/// Returns None if the amount overflows u64.
+fn calculate_amount_out_in_range(
+    sqrt_price_current_x64: u128,
+    sqrt_price_target_x64: u128,
+    liquidity: u128,
+    zero_for_one: bool,
+) -> Option<u64> {
+    if zero_for_one {
+        liquidity_math::get_delta_amount_1_unsigned(
+            sqrt_price_target_x64,
+            sqrt_price_current_x64,
+            liquidity,
+            false,
+        )
+        .ok()
+    } else {
+        liquidity_math::get_delta_amount_0_unsigned(
+            sqrt_price_current_x64,
+            sqrt_price_target_x64,
+            liquidity,
+            false,
+        )
+        .ok()
+    }
+}
+
+#[cfg(test)]
+mod swap_math_test {
+    use super::*;
+    use crate::libraries::tick_math;
+
+    #[test]
+    fn exact_input_reaches_target_when_amount_is_sufficient() {
+        let current = tick_math::get_sqrt_price_at_tick(0).unwrap();
+        let target = tick_math::get_sqrt_price_at_tick(-10).unwrap();
+        let step = compute_swap_step(current, target, 1_000_000_000, u64::MAX / 2, 3000, true).unwrap();
+        assert_eq!(step.sqrt_price_next_x64, target);
+        assert!(step.amount_in > 0);
+        assert!(step.amount_out > 0);
+        assert!(step.fee_amount > 0);
+    }
+
+    #[test]
+    fn exact_input_consumes_whole_amount_when_target_is_far() {
+        let current = tick_math::get_sqrt_price_at_tick(0).unwrap();
+        let target = tick_math::get_sqrt_price_at_tick(10_000).unwrap();
+        let amount = 1_000;
+        let step = compute_swap_step(current, target, 1_000_000_000, amount, 3000, true).unwrap();
+        assert!(step.sqrt_price_next_x64 > current && step.sqrt_price_next_x64 < target);
+        assert_eq!(step.amount_in + step.fee_amount, amount);
+    }
+
+    #[test]
+    fn exact_output_pays_exact_amount_when_target_is_far() {
+        let current = tick_math::get_sqrt_price_at_tick(0).unwrap();
+        let target = tick_math::get_sqrt_price_at_tick(-10_000).unwrap();
+        let amount = 1_000;
+        let step = compute_swap_step(current, target, 1_000_000_000, amount, 3000, false).unwrap();
+        assert!(step.sqrt_price_next_x64 < current && step.sqrt_price_next_x64 > target);
+        assert_eq!(step.amount_out, amount);
+        // the input is rounded up in the pool's favour
+        assert!(step.amount_in >= amount);
+        assert!(step.fee_amount > 0);
+    }
+
+    #[test]
+    fn exact_output_stops_at_target_when_amount_is_large() {
+        let current = tick_math::get_sqrt_price_at_tick(0).unwrap();
+        let target = tick_math::get_sqrt_price_at_tick(10).unwrap();
+        let step = compute_swap_step(current, target, 1_000_000_000, u64::MAX / 2, 3000, false).unwrap();
+        assert_eq!(step.sqrt_price_next_x64, target);
+        assert!(step.amount_out < u64::MAX / 2);
+    }
+
+    mod fuzz_tests {
+        use super::*;
+        use crate::constants::{SQRT_PRICE_X64_MAX, SQRT_PRICE_X64_MIN};
+        use proptest::prelude::*;
+
+        proptest! {
+            #[test]
+            fn compute_swap_step_exact_input_test (
+                sqrt_price_current_x64 in SQRT_PRICE_X64_MIN..SQRT_PRICE_X64_MAX,
+                sqrt_price_target_x64 in SQRT_PRICE_X64_MIN..SQRT_PRICE_X64_MAX,
+                liquidity in 1..u64::MAX as u128,
+                amount_remaining in 1..u64::MAX,
+                fee_rate in 1..FEE_RATE_DENOMINATOR_VALUE / 2,
+            ) {
+                prop_assume!(sqrt_price_current_x64 != sqrt_price_target_x64);
+                let zero_for_one = sqrt_price_current_x64 > sqrt_price_target_x64;
+                let result = compute_swap_step(
+                    sqrt_price_current_x64,
+                    sqrt_price_target_x64,
+                    liquidity,
+                    amount_remaining,
+                    fee_rate,
+                    true,
+                );
+                prop_assume!(result.is_ok());
+                let step = result.unwrap();
+
+                let amount_used = step.amount_in as u128 + step.fee_amount as u128;
+                assert!(amount_used <= amount_remaining as u128);
+                // the whole input is spent unless the target was reached
+                if step.sqrt_price_next_x64 != sqrt_price_target_x64 {
+                    assert_eq!(amount_used, amount_remaining as u128);
+                }
+                // the price never overshoots the target
+                if zero_for_one {
+                    assert!(step.sqrt_price_next_x64 <= sqrt_price_current_x64);
+                    assert!(step.sqrt_price_next_x64 >= sqrt_price_target_x64);
+                } else {
+                    assert!(step.sqrt_price_next_x64 >= sqrt_price_current_x64);
+                    assert!(step.sqrt_price_next_x64 <= sqrt_price_target_x64);
+                }
+            }
+
+            #[test]
+            fn compute_swap_step_exact_output_test (
+                sqrt_price_current_x64 in SQRT_PRICE_X64_MIN..SQRT_PRICE_X64_MAX,
+                sqrt_price_target_x64 in SQRT_PRICE_X64_MIN..SQRT_PRICE_X64_MAX,
+                liquidity in 1..u64::MAX as u128,
+                amount_remaining in 1..u64::MAX,
+                fee_rate in 1..FEE_RATE_DENOMINATOR_VALUE / 2,
+            ) {
+                prop_assume!(sqrt_price_current_x64 != sqrt_price_target_x64);
+                let zero_for_one = sqrt_price_current_x64 > sqrt_price_target_x64;
+                let result = compute_swap_step(
+                    sqrt_price_current_x64,
+                    sqrt_price_target_x64,
+                    liquidity,
+                    amount_remaining,
+                    fee_rate,
+                    false,
+                );
+                prop_assume!(result.is_ok());
+                let step = result.unwrap();
+
+                assert!(step.amount_out <= amount_remaining);
+                // the whole output is delivered unless the target was reached
+                if step.sqrt_price_next_x64 != sqrt_price_target_x64 {
+                    assert_eq!(step.amount_out, amount_remaining);
+                }
+                if zero_for_one {
+                    assert!(step.sqrt_price_next_x64 <= sqrt_price_current_x64);
+                    assert!(step.sqrt_price_next_x64 >= sqrt_price_target_x64);
+                } else {
+                    assert!(step.sqrt_price_next_x64 >= sqrt_price_current_x64);
+                    assert!(step.sqrt_price_next_x64 <= sqrt_price_target_x64);
+                }
+            }
+
+            #[test]
+            fn compute_swap_step_fee_rounds_up_test (
+                sqrt_price_current_x64 in SQRT_PRICE_X64_MIN..SQRT_PRICE_X64_MAX,
+                sqrt_price_target_x64 in SQRT_PRICE_X64_MIN..SQRT_PRICE_X64_MAX,
+                liquidity in 1..u64::MAX as u128,
+                amount_remaining in 1..u64::MAX,
+                fee_rate in 1..FEE_RATE_DENOMINATOR_VALUE / 2,
+                is_base_input in proptest::bool::ANY,
+            ) {
+                prop_assume!(sqrt_price_current_x64 != sqrt_price_target_x64);
+                let result = compute_swap_step(
+                    sqrt_price_current_x64,
+                    sqrt_price_target_x64,
+                    liquidity,
+                    amount_remaining,
+                    fee_rate,
+                    is_base_input,
+                );
+                prop_assume!(result.is_ok());
+                let step = result.unwrap();
+
+                // the fee is at least fee_rate of the total paid by the swapper
+                let paid = step.amount_in as u128 + step.fee_amount as u128;
+                assert!(
+                    step.fee_amount as u128 * FEE_RATE_DENOMINATOR_VALUE as u128
+                        >= paid * fee_rate as u128
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use crate::constants::{TICK_ARRAY_BITMAP_SIZE, TICK_ARRAY_SIZE};
+
+const BITMAP_BITS: i32 = TICK_ARRAY_BITMAP_SIZE as i32 * 64;
+
+/// Searches the bitmap of a pool for the nearest initialized tick array left
+/// (zero_for_one) or right of the one starting at current_start_index, which
+/// is itself never returned.
+///
+/// bitmap_pos holds one bit per tick array starting at or above 0, bitmap_neg
+/// one per array below 0, the array starting at -tick_count being bit 0.
+/// Returns None once the search runs off the end of the bitmap.
+pub fn next_initialized_tick_array_start_index(
+    bitmap_pos: &[u64],
+    bitmap_neg: &[u64],
+    current_start_index: i32,
+    tick_spacing: u16,
+    zero_for_one: bool,
+) -> Option<i32> {
+    let ticks_in_array = TICK_ARRAY_SIZE as i32 * i32::from(tick_spacing);
+    let offset = current_start_index.div_euclid(ticks_in_array);
+
+    // array offsets below 0 are stored in bitmap_neg at -offset - 1
+    let next_offset = if zero_for_one {
+        let from = offset - 1;
+        if from >= 0 {
+            prev_set_bit(bitmap_pos, from.min(BITMAP_BITS - 1) as usize)
+                .map(|bit| bit as i32)
+                .or_else(|| next_set_bit(bitmap_neg, 0).map(|bit| -(bit as i32) - 1))
+        } else {
+            next_set_bit(bitmap_neg, (-from - 1) as usize).map(|bit| -(bit as i32) - 1)
+        }
+    } else {
+        let from = offset + 1;
+        if from < 0 {
+            prev_set_bit(bitmap_neg, (-from - 1).min(BITMAP_BITS - 1) as usize)
+                .map(|bit| -(bit as i32) - 1)
+                .or_else(|| next_set_bit(bitmap_pos, 0).map(|bit| bit as i32))
+        } else {
+            next_set_bit(bitmap_pos, from as usize).map(|bit| bit as i32)
+        }
+    }?;
+    Some(next_offset * ticks_in_array)
+}
+
+/// The lowest set bit at or above from
+fn next_set_bit(words: &[u64], from: usize) -> Option<usize> {
+    let word_idx = from / 64;
+    let word = *words.get(word_idx)? & (u64::MAX << (from % 64));
+    if word != 0 {
+        return Some(word_idx * 64 + word.trailing_zeros() as usize);
+    }
+    words[word_idx + 1..]
+        .iter()
+        .position(|word| *word != 0)
+        .map(|i| (word_idx + 1 + i) * 64 + words[word_idx + 1 + i].trailing_zeros() as usize)
+}
+
+/// The highest set bit at or below from, which must be within words
+fn prev_set_bit(words: &[u64], from: usize) -> Option<usize> {
+    let word_idx = from / 64;
+    let word = words[word_idx] & (u64::MAX >> (63 - from % 64));
+    if word != 0 {
+        return Some(word_idx * 64 + 63 - word.leading_zeros() as usize);
+    }
+    words[..word_idx]
+        .iter()
+        .rposition(|word| *word != 0)
+        .map(|i| i * 64 + 63 - words[i].leading_zeros() as usize)
+}
+
+#[cfg(test)]
+mod tick_array_bitmap_math_test {
+    use super::*;
+
+    const SIZE: usize = TICK_ARRAY_BITMAP_SIZE as usize;
+
+    /// Sets the bit of the array at offset, counted in tick arrays from 0
+    fn set(bitmap_pos: &mut [u64; SIZE], bitmap_neg: &mut [u64; SIZE], offset: i32) {
+        let (bitmap, bit) = if offset >= 0 {
+            (bitmap_pos, offset as usize)
+        } else {
+            (bitmap_neg, (-offset - 1) as usize)
+        };
+        bitmap[bit / 64] |= 1 << (bit % 64);
+    }
+
+    fn is_set(bitmap_pos: &[u64; SIZE], bitmap_neg: &[u64; SIZE], offset: i32) -> bool {
+        let (bitmap, bit) = if offset >= 0 {
+            (bitmap_pos, offset as usize)
+        } else {
+            (bitmap_neg, (-offset - 1) as usize)
+        };
+        bitmap[bit / 64] & (1 << (bit % 64)) != 0
+    }
+
+    #[test]
+    fn empty_bitmap_has_no_next_array() {
+        let bitmap = [0; SIZE];
+        assert_eq!(next_initialized_tick_array_start_index(&bitmap, &bitmap, 0, 1, true), None);
+        assert_eq!(next_initialized_tick_array_start_index(&bitmap, &bitmap, 0, 1, false), None);
+    }
+
+    #[test]
+    fn search_crosses_from_negative_to_positive_half() {
+        let (mut bitmap_pos, mut bitmap_neg) = ([0; SIZE], [0; SIZE]);
+        set(&mut bitmap_pos, &mut bitmap_neg, 3);
+        let tick_spacing = 10;
+        let ticks_in_array = TICK_ARRAY_SIZE as i32 * 10;
+        assert_eq!(
+            next_initialized_tick_array_start_index(
+                &bitmap_pos, &bitmap_neg, -5 * ticks_in_array, tick_spacing, false),
+            Some(3 * ticks_in_array)
+        );
+        assert_eq!(
+            next_initialized_tick_array_start_index(
+                &bitmap_pos, &bitmap_neg, 3 * ticks_in_array, tick_spacing, false),
+            None
+        );
+    }
+
+    #[test]
+    fn search_crosses_from_positive_to_negative_half() {
+        let (mut bitmap_pos, mut bitmap_neg) = ([0; SIZE], [0; SIZE]);
+        set(&mut bitmap_pos, &mut bitmap_neg, -1);
+        set(&mut bitmap_pos, &mut bitmap_neg, -200);
+        let ticks_in_array = TICK_ARRAY_SIZE as i32;
+        assert_eq!(
+            next_initialized_tick_array_start_index(&bitmap_pos, &bitmap_neg, 0, 1, true),
+            Some(-ticks_in_array)
+        );
+        assert_eq!(
+            next_initialized_tick_array_start_index(&bitmap_pos, &bitmap_neg, -ticks_in_array, 1, true),
+            Some(-200 * ticks_in_array)
+        );
+    }
+
+    mod fuzz_tests {
+        use super::*;
+        use proptest::prelude::*;
+
+        proptest! {
+            #[test]
+            fn next_initialized_tick_array_start_index_test (
+                offsets in proptest::collection::vec(-BITMAP_BITS..BITMAP_BITS, 0..8),
+                current_offset in -BITMAP_BITS - 2..BITMAP_BITS + 2,
+                tick_spacing in 1..200u16,
+                zero_for_one in proptest::bool::ANY,
+            ) {
+                let (mut bitmap_pos, mut bitmap_neg) = ([0; SIZE], [0; SIZE]);
+                for offset in offsets {
+                    set(&mut bitmap_pos, &mut bitmap_neg, offset);
+                }
+                let ticks_in_array = TICK_ARRAY_SIZE as i32 * i32::from(tick_spacing);
+
+                let expected = if zero_for_one {
+                    (-BITMAP_BITS..current_offset.min(BITMAP_BITS))
+                        .rev()
+                        .find(|&offset| is_set(&bitmap_pos, &bitmap_neg, offset))
+                } else {
+                    ((current_offset + 1).max(-BITMAP_BITS)..BITMAP_BITS)
+                        .find(|&offset| is_set(&bitmap_pos, &bitmap_neg, offset))
+                };
+                assert_eq!(
+                    next_initialized_tick_array_start_index(
+                        &bitmap_pos,
+                        &bitmap_neg,
+                        current_offset * ticks_in_array,
+                        tick_spacing,
+                        zero_for_one,
+                    ),
+                    expected.map(|offset| offset * ticks_in_array)
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{DYNAMIC_FEE_MAX_SPACINGS, FEE_RATE_DENOMINATOR_VALUE};
+use crate::error::ErrorCode;
+
+use super::PoolState;
+
+/// A fee tier created by the admin. Pools are created against a config and
+/// take its tick spacing and fee rates.
+#[account]
+#[derive(Default, Debug)]
+pub struct AmmConfig {
+    pub bump: u8,
+    pub index: u16,
+    /// The admin who created the config
+    pub owner: Pubkey,
+    /// The trade fee, in hundredths of a bip (10^-6)
+    pub trade_fee_rate: u32,
+    /// The share of the trade fee taken by the protocol, in hundredths of a bip (10^-6)
+    pub protocol_fee_rate: u32,
+    pub tick_spacing: u16,
+    /// Cap on the fee of pools in dynamic-fee mode, in hundredths of a bip (10^-6).
+    /// Zero leaves dynamic fees off for the tier.
+    pub max_dynamic_fee_rate: u32,
+}
+
+impl AmmConfig {
+    pub const LEN: usize =
+        1 +
+        2 +
+        32 +
+        4 +
+        4 +
+        2 +
+        4;
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn initialize(
+        &mut self,
+        bump: u8,
+        index: u16,
+        owner: Pubkey,
+        tick_spacing: u16,
+        trade_fee_rate: u32,
+        protocol_fee_rate: u32,
+        max_dynamic_fee_rate: u32,
+    ) -> Result<()> {
+        require!(tick_spacing > 0, ErrorCode::TickSpacingZero);
+        require_gt!(FEE_RATE_DENOMINATOR_VALUE, trade_fee_rate, ErrorCode::InvalidFeeRate);
+        require_gte!(FEE_RATE_DENOMINATOR_VALUE, protocol_fee_rate, ErrorCode::InvalidFeeRate);
+        if max_dynamic_fee_rate > 0 {
+            require_gte!(max_dynamic_fee_rate, trade_fee_rate, ErrorCode::InvalidFeeRate);
+            require_gt!(FEE_RATE_DENOMINATOR_VALUE, max_dynamic_fee_rate, ErrorCode::InvalidFeeRate);
+            // swaps split their steps wherever the fee changes, keep that within the compute budget
+            require_gte!(
+                u64::from(DYNAMIC_FEE_MAX_SPACINGS),
+                PoolState::get_capped_volatility(tick_spacing, trade_fee_rate, max_dynamic_fee_rate),
+                ErrorCode::InvalidFeeRate
+            );
+        }
+        self.bump = bump;
+        self.index = index;
+        self.owner = owner;
+        self.tick_spacing = tick_spacing;
+        self.trade_fee_rate = trade_fee_rate;
+        self.protocol_fee_rate = protocol_fee_rate;
+        self.max_dynamic_fee_rate = max_dynamic_fee_rate;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod config_test {
+    use super::*;
+
+    fn initialize(tick_spacing: u16, trade_fee_rate: u32, max_dynamic_fee_rate: u32) -> Result<()> {
+        AmmConfig::default().initialize(
+            0,
+            0,
+            Pubkey::default(),
+            tick_spacing,
+            trade_fee_rate,
+            0,
+            max_dynamic_fee_rate,
+        )
+    }
+
+    #[test]
+    fn dynamic_fee_cap_must_be_reached_within_max_spacings() {
+        let fee_rate_per_spacing = 60 * crate::constants::DYNAMIC_FEE_CONTROL;
+        let max_fee_rate = 3000 + DYNAMIC_FEE_MAX_SPACINGS * fee_rate_per_spacing;
+        assert!(initialize(60, 3000, 0).is_ok());
+        assert!(initialize(60, 3000, max_fee_rate).is_ok());
+        assert_eq!(
+            initialize(60, 3000, max_fee_rate + 1).unwrap_err(),
+            ErrorCode::InvalidFeeRate.into()
+        );
+        assert!(initialize(1, 3000, 100_000).is_err());
+    }
+}
@@ -0,0 +1,258 @@
+use anchor_lang::prelude::*;
+
+use super::TickState;
+
+/// A one-sided order resting on a single tick. It is filled at the tick's price
+/// by swaps that reach the tick, and takes no part in the pool's liquidity.
+#[account]
+#[derive(Default, Debug)]
+pub struct LimitOrderState {
+    pub pool_id: Pubkey,
+    pub owner: Pubkey,
+    pub tick_index: i32,
+    /// Sells token_1 for token_0 and is filled by zero_for_one swaps if true,
+    /// sells token_0 for token_1 otherwise
+    pub zero_for_one: bool,
+    /// Epoch id of the batch of orders on the tick the order joined
+    pub epoch: u64,
+    /// The amount the order sells
+    pub amount: u64,
+    /// The order's shares of its batch, at share_scale
+    pub shares: u64,
+    /// The order_share_scale of the tick when the order joined
+    pub share_scale: u32,
+    /// The filled amount already paid out to the owner
+    pub filled_last: u64,
+}
+
+impl LimitOrderState {
+    pub const LEN: usize =
+        32 +
+        32 +
+        4 +
+        1 +
+        8 +
+        8 +
+        8 +
+        4 +
+        8;
+
+    /// tick is the order's tick after the order joined it
+    #[allow(clippy::too_many_arguments)]
+    pub fn initialize(
+        &mut self,
+        pool_id: Pubkey,
+        owner: Pubkey,
+        tick_index: i32,
+        zero_for_one: bool,
+        amount: u64,
+        (epoch, shares, share_scale): (u64, u64, u32),
+        tick: &TickState,
+    ) {
+        self.pool_id = pool_id;
+        self.owner = owner;
+        self.tick_index = tick_index;
+        self.zero_for_one = zero_for_one;
+        self.epoch = epoch;
+        self.amount = amount;
+        self.shares = shares;
+        self.share_scale = share_scale;
+        // shares are rounded down, what that costs the order does not count as filled
+        self.filled_last = self.get_filled(tick);
+    }
+
+    /// Whether the order's batch is still resting on the tick
+    pub fn is_open(&self, tick: &TickState) -> bool {
+        tick.order_epoch == self.epoch
+    }
+
+    /// The filled amount of the order as of now, given the tick it rests on
+    pub fn get_filled(&self, tick: &TickState) -> u64 {
+        if !self.is_open(tick) {
+            return self.amount;
+        }
+        self.amount
+            .saturating_sub(tick.get_order_remaining(self.shares, self.share_scale, true))
+    }
+
+    /// The most the order can get back when cancelled, its amount less what has been paid out
+    pub fn get_unfilled_max(&self) -> u64 {
+        self.amount - self.filled_last
+    }
+
+    /// Marks the filled amount as paid out and returns how much was not yet,
+    /// in the token the order sells
+    pub fn settle_filled(&mut self, tick: &TickState) -> u64 {
+        let filled = self.get_filled(tick).max(self.filled_last);
+        let amount = filled - self.filled_last;
+        self.filled_last = filled;
+        amount
+    }
+}
+
+#[cfg(test)]
+mod limit_order_test {
+    use super::*;
+    use crate::error::ErrorCode;
+    use crate::state::{Observation, PoolState};
+
+    const TICK_INDEX: i32 = -100;
+
+    fn open_order(
+        pool_state: &mut PoolState,
+        tick: &mut TickState,
+        zero_for_one: bool,
+        amount: u64,
+    ) -> Result<LimitOrderState> {
+        let order = tick.add_order(pool_state, &Observation::default(), TICK_INDEX, zero_for_one, amount)?;
+        let mut limit_order = LimitOrderState::default();
+        limit_order.initialize(
+            Pubkey::default(),
+            Pubkey::default(),
+            TICK_INDEX,
+            zero_for_one,
+            amount,
+            order,
+            tick,
+        );
+        Ok(limit_order)
+    }
+
+    fn cancel_order(tick: &mut TickState, limit_order: &LimitOrderState) -> u64 {
+        tick.remove_order(limit_order.shares, limit_order.share_scale, limit_order.get_unfilled_max())
+    }
+
+    #[test]
+    fn open_fill_join_cancel_and_collect() {
+        let mut pool_state = PoolState::default();
+        let mut tick = TickState::default();
+
+        let mut order_a = open_order(&mut pool_state, &mut tick, true, 1000).unwrap();
+        assert_eq!(order_a.epoch, 1);
+        assert!(tick.valid() && tick.has_orders_for(true) && !tick.has_orders_for(false));
+        assert_eq!(order_a.settle_filled(&tick), 0);
+
+        tick.fill_orders(600);
+        assert_eq!(order_a.settle_filled(&tick), 600);
+        assert_eq!(order_a.settle_filled(&tick), 0);
+
+        // joining the partly filled batch takes as many shares per token as the batch has left
+        let mut order_b = open_order(&mut pool_state, &mut tick, true, 400).unwrap();
+        assert_eq!(order_b.epoch, 1);
+        assert_eq!(order_b.shares, 1000);
+        assert_eq!(order_b.settle_filled(&tick), 0);
+        assert_eq!({ tick.order_remaining }, 800);
+
+        // the next fill is shared by both orders alike
+        tick.fill_orders(400);
+        assert_eq!(order_a.settle_filled(&tick), 200);
+        assert_eq!(order_b.get_filled(&tick), 200);
+
+        // cancelling pays out the unfilled rest and leaves the other order on the tick
+        assert!(order_a.is_open(&tick));
+        assert_eq!(cancel_order(&mut tick, &order_a), 200);
+        assert_eq!({ tick.order_remaining }, 200);
+        assert!(order_b.is_open(&tick));
+
+        // filling the rest closes the batch, the order counts as filled in full
+        tick.fill_orders(200);
+        assert!(!tick.valid());
+        assert!(!order_b.is_open(&tick));
+        assert_eq!(order_b.settle_filled(&tick), 400);
+
+        // the next order on the tick opens a new batch
+        let order_c = open_order(&mut pool_state, &mut tick, false, 10).unwrap();
+        assert_eq!(order_c.epoch, 2);
+        assert!(!order_b.is_open(&tick));
+    }
+
+    #[test]
+    fn orders_on_one_tick_sell_the_same_token() {
+        let mut pool_state = PoolState::default();
+        let mut tick = TickState::default();
+        open_order(&mut pool_state, &mut tick, true, 1000).unwrap();
+        assert_eq!(
+            open_order(&mut pool_state, &mut tick, false, 1000).unwrap_err(),
+            ErrorCode::LimitOrderSideConflict.into()
+        );
+    }
+
+    #[test]
+    fn cancelling_the_last_order_clears_the_batch() {
+        let mut pool_state = PoolState::default();
+        let mut tick = TickState::default();
+        let order = open_order(&mut pool_state, &mut tick, true, 1000).unwrap();
+        tick.fill_orders(999);
+        assert_eq!(cancel_order(&mut tick, &order), 1);
+        assert!(!tick.valid());
+    }
+
+    #[test]
+    fn joining_a_batch_filled_down_to_dust() {
+        let mut pool_state = PoolState::default();
+        let mut tick = TickState::default();
+        let mut order_a = open_order(&mut pool_state, &mut tick, true, u64::MAX / 2).unwrap();
+        tick.fill_orders(u64::MAX / 2 - 1);
+        assert_eq!(order_a.settle_filled(&tick), u64::MAX / 2 - 1);
+
+        // the shares a join needs no longer fit in u64, so the batch is scaled down
+        let amount = u64::MAX / 4;
+        let mut order_b = open_order(&mut pool_state, &mut tick, true, amount).unwrap();
+        assert!({ tick.order_share_scale } > 0);
+        assert_eq!(order_b.share_scale, { tick.order_share_scale });
+        assert_eq!(order_b.settle_filled(&tick), 0);
+
+        tick.fill_orders(amount / 2);
+        let filled_b = order_b.settle_filled(&tick);
+        assert!(filled_b <= amount / 2 && amount / 2 - filled_b <= 1);
+        assert!(order_a.settle_filled(&tick) <= 1);
+
+        let unfilled_b = cancel_order(&mut tick, &order_b);
+        assert!(filled_b + unfilled_b <= amount && amount - filled_b - unfilled_b <= 1);
+    }
+
+    mod fuzz_tests {
+        use super::*;
+        use proptest::prelude::*;
+
+        proptest! {
+            #[test]
+            fn limit_order_batch_test (
+                amounts in proptest::collection::vec(1..u64::MAX / 64, 1..8),
+                fill_shares in proptest::collection::vec(0..=1000u64, 8),
+            ) {
+                let mut pool_state = PoolState::default();
+                let mut tick = TickState::default();
+                let mut orders = Vec::new();
+                let (mut filled_total, mut paid_total) = (0, 0);
+
+                // each order joins after a random part of what is left has been filled
+                for (amount, fill_share) in amounts.iter().zip(fill_shares) {
+                    let order = open_order(&mut pool_state, &mut tick, true, *amount).unwrap();
+                    prop_assume!(order.epoch == 1);
+                    orders.push(order);
+                    let fill = (tick.order_remaining / 1000 * fill_share).min(tick.order_remaining - 1);
+                    tick.fill_orders(fill);
+                    filled_total += u128::from(fill);
+                }
+
+                // orders never claim more than was filled or refund more than is left
+                let mut unfilled_total = 0;
+                for order in orders.iter_mut() {
+                    let filled = order.settle_filled(&tick);
+                    let unfilled = if order.is_open(&tick) {
+                        cancel_order(&mut tick, order)
+                    } else {
+                        0
+                    };
+                    assert!(order.filled_last + unfilled <= order.amount);
+                    paid_total += u128::from(filled);
+                    unfilled_total += u128::from(unfilled);
+                }
+                let deposited_total: u128 = amounts.iter().map(|amount| u128::from(*amount)).sum();
+                assert!(paid_total <= filled_total + orders.len() as u128);
+                assert!(unfilled_total <= deposited_total - filled_total);
+            }
+        }
+    }
+}
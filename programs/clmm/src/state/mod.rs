@@ -6,25 +6,17 @@ pub use tick_index_check;
 pub mod pool;
 pub use pool::*;
 
-use anchor_lang::prelude::*;
+pub mod position;
+pub use position::*;
 
-#[account]
-#[derive(Default, Debug)]
-pub struct PositionState {
-    pub liquidity: u128,
-    pub fee_growth_inside_0_last_x64: u128,
-    pub fee_growth_inside_1_last_x64: u128,
-}
+pub mod config;
+pub use config::*;
 
-impl PositionState {
-    pub const LEN: usize =
-        16 +
-        16 +
-        16;
+pub mod support_mint;
+pub use support_mint::*;
 
-    pub fn initialize(&mut self, l_delta: u128, fee_g_0: u128, fee_g_1: u128) {
-        self.liquidity = l_delta;
-        self.fee_growth_inside_0_last_x64 = fee_g_0;
-        self.fee_growth_inside_1_last_x64 = fee_g_1;
-    }
-}
\ No newline at end of file
+pub mod oracle;
+pub use oracle::*;
+
+pub mod limit_order;
+pub use limit_order::*;
@@ -0,0 +1,281 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::OBSERVATION_NUM;
+use crate::error::ErrorCode;
+use crate::libraries::big_num::U128;
+use crate::libraries::full_math::MulDiv;
+
+/// A snapshot of the pool's accumulators at block_timestamp
+#[zero_copy]
+#[repr(C, packed)]
+#[derive(Default, Debug)]
+pub struct Observation {
+    pub block_timestamp: u32,
+    /// The tick multiplied by the seconds elapsed, summed since the pool was created
+    pub tick_cumulative: i64,
+    /// The seconds elapsed divided by the in-range liquidity, summed since the pool was created, Q64.64
+    pub seconds_per_liquidity_cumulative_x64: u128,
+}
+
+impl Observation {
+    pub const LEN: usize =
+        4 +
+        8 +
+        16;
+
+    /// Rolls the accumulators forward to block_timestamp, assuming tick and
+    /// liquidity held since the observation was taken
+    pub fn transform(&self, block_timestamp: u32, tick: i32, liquidity: u128) -> Observation {
+        let delta = block_timestamp.wrapping_sub(self.block_timestamp);
+        Observation {
+            block_timestamp,
+            tick_cumulative: self.tick_cumulative.wrapping_add(i64::from(tick) * i64::from(delta)),
+            seconds_per_liquidity_cumulative_x64: self.seconds_per_liquidity_cumulative_x64.wrapping_add(
+                (u128::from(delta) << 64) / liquidity.max(1),
+            ),
+        }
+    }
+
+    pub fn initialized(&self) -> bool {
+        self.block_timestamp != 0
+    }
+}
+
+/// Ring buffer of observations, one account per pool. A new observation is
+/// written at most once per second, before the pool's tick or liquidity changes.
+#[account(zero_copy)]
+#[repr(C, packed)]
+#[derive(Debug)]
+pub struct ObservationState {
+    pub pool_id: Pubkey,
+    /// Index of the most recently written observation
+    pub observation_index: u16,
+    pub observations: [Observation; OBSERVATION_NUM as usize],
+}
+
+impl ObservationState {
+    pub const LEN: usize =
+        32 +
+        2 +
+        Observation::LEN * OBSERVATION_NUM as usize;
+
+    pub fn initialize(&mut self, pool_id: Pubkey, block_timestamp: u32) {
+        self.pool_id = pool_id;
+        self.observation_index = 0;
+        self.observations = [Observation::default(); OBSERVATION_NUM as usize];
+        self.observations[0].block_timestamp = block_timestamp;
+    }
+
+    /// Records the tick and liquidity that held up to block_timestamp.
+    /// Must be called before the pool's tick or liquidity changes.
+    pub fn update(&mut self, block_timestamp: u32, tick: i32, liquidity: u128) {
+        let last = self.observations[self.observation_index as usize];
+        if last.block_timestamp == block_timestamp {
+            return;
+        }
+        let next_index = (self.observation_index + 1) % OBSERVATION_NUM as u16;
+        self.observations[next_index as usize] = last.transform(block_timestamp, tick, liquidity);
+        self.observation_index = next_index;
+    }
+
+    /// The accumulators as of block_timestamp, given the pool's current tick and liquidity
+    pub fn snapshot(&self, block_timestamp: u32, tick: i32, liquidity: u128) -> Observation {
+        self.observations[self.observation_index as usize].transform(block_timestamp, tick, liquidity)
+    }
+
+    /// Returns (tick_cumulative, seconds_per_liquidity_cumulative_x64) as of
+    /// seconds_ago before block_timestamp, interpolating between observations.
+    /// tick and liquidity are the pool's current values.
+    pub fn observe(
+        &self,
+        block_timestamp: u32,
+        seconds_ago: u32,
+        tick: i32,
+        liquidity: u128,
+    ) -> Result<(i64, u128)> {
+        let target = block_timestamp
+            .checked_sub(seconds_ago)
+            .ok_or(ErrorCode::ObservationTooOld)?;
+
+        let last = self.observations[self.observation_index as usize];
+        if target >= last.block_timestamp {
+            let observation = self.snapshot(target, tick, liquidity);
+            return Ok((observation.tick_cumulative, observation.seconds_per_liquidity_cumulative_x64));
+        }
+
+        // The slot after the latest one holds the oldest observation once the buffer has wrapped
+        let oldest_index = (self.observation_index as usize + 1) % OBSERVATION_NUM as usize;
+        let (oldest_index, len) = if self.observations[oldest_index].initialized() {
+            (oldest_index, OBSERVATION_NUM as usize)
+        } else {
+            (0, self.observation_index as usize + 1)
+        };
+        let at = |i: usize| self.observations[(oldest_index + i) % OBSERVATION_NUM as usize];
+        let oldest_timestamp = at(0).block_timestamp;
+        require_gte!(target, oldest_timestamp, ErrorCode::ObservationTooOld);
+
+        // Binary search for the last observation at or before target,
+        // the latest one is known to be after it
+        let (mut low, mut high) = (0, len - 1);
+        while high - low > 1 {
+            let mid = (low + high) / 2;
+            if at(mid).block_timestamp <= target {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        let before = at(low);
+        let after = at(high);
+        if target == before.block_timestamp {
+            return Ok((before.tick_cumulative, before.seconds_per_liquidity_cumulative_x64));
+        }
+
+        let observation_time_delta = after.block_timestamp - before.block_timestamp;
+        let target_delta = target - before.block_timestamp;
+        let tick_cumulative = before.tick_cumulative
+            + (after.tick_cumulative - before.tick_cumulative) / i64::from(observation_time_delta)
+                * i64::from(target_delta);
+        let seconds_per_liquidity_cumulative_x64 = before.seconds_per_liquidity_cumulative_x64
+            + U128::from(after.seconds_per_liquidity_cumulative_x64 - before.seconds_per_liquidity_cumulative_x64)
+                .mul_div_floor(U128::from(target_delta), U128::from(observation_time_delta))
+                .unwrap()
+                .as_u128();
+        Ok((tick_cumulative, seconds_per_liquidity_cumulative_x64))
+    }
+
+    /// The time-weighted average tick over the window_seconds before block_timestamp,
+    /// rounded towards negative infinity
+    pub fn get_twap_tick(
+        &self,
+        block_timestamp: u32,
+        window_seconds: u32,
+        tick: i32,
+        liquidity: u128,
+    ) -> Result<i32> {
+        require!(window_seconds > 0, ErrorCode::InvalidTwapWindow);
+        let (tick_cumulative_end, _) = self.observe(block_timestamp, 0, tick, liquidity)?;
+        let (tick_cumulative_start, _) = self.observe(block_timestamp, window_seconds, tick, liquidity)?;
+        let tick_cumulative_delta = tick_cumulative_end - tick_cumulative_start;
+        let window = i64::from(window_seconds);
+        let mut twap_tick = tick_cumulative_delta / window;
+        if tick_cumulative_delta < 0 && tick_cumulative_delta % window != 0 {
+            twap_tick -= 1;
+        }
+        Ok(twap_tick as i32)
+    }
+}
+
+#[cfg(test)]
+mod oracle_test {
+    use super::*;
+
+    fn new_observation_state(block_timestamp: u32) -> ObservationState {
+        let mut observation_state = ObservationState {
+            pool_id: Pubkey::default(),
+            observation_index: 0,
+            observations: [Observation::default(); OBSERVATION_NUM as usize],
+        };
+        observation_state.initialize(Pubkey::default(), block_timestamp);
+        observation_state
+    }
+
+    /// Tick 10 over [1000, 1010), tick -5 over [1010, 1030), tick 20 since
+    fn three_observations() -> ObservationState {
+        let mut observation_state = new_observation_state(1000);
+        observation_state.update(1010, 10, 100);
+        observation_state.update(1030, -5, 100);
+        observation_state
+    }
+
+    fn tick_cumulative_at(observation_state: &ObservationState, block_timestamp: u32, seconds_ago: u32) -> i64 {
+        observation_state.observe(block_timestamp, seconds_ago, 20, 100).unwrap().0
+    }
+
+    #[test]
+    fn observe_at_observations() {
+        let observation_state = three_observations();
+        assert_eq!(tick_cumulative_at(&observation_state, 1040, 40), 0);
+        assert_eq!(tick_cumulative_at(&observation_state, 1040, 30), 100);
+        assert_eq!(tick_cumulative_at(&observation_state, 1040, 10), 0);
+    }
+
+    #[test]
+    fn observe_after_latest_observation_uses_current_tick() {
+        let observation_state = three_observations();
+        assert_eq!(tick_cumulative_at(&observation_state, 1040, 0), 200);
+        assert_eq!(tick_cumulative_at(&observation_state, 1040, 5), 100);
+    }
+
+    #[test]
+    fn observe_interpolates_between_observations() {
+        let observation_state = three_observations();
+        assert_eq!(tick_cumulative_at(&observation_state, 1040, 35), 50);
+        assert_eq!(tick_cumulative_at(&observation_state, 1040, 20), 50);
+        assert_eq!(tick_cumulative_at(&observation_state, 1040, 15), 25);
+
+        // a quarter of the way from 1010 to 1030, with liquidity unchanged
+        let (_, seconds_per_liquidity) = observation_state.observe(1040, 25, 20, 100).unwrap();
+        let before = observation_state.observations[1].seconds_per_liquidity_cumulative_x64;
+        let after = observation_state.observations[2].seconds_per_liquidity_cumulative_x64;
+        assert_eq!(seconds_per_liquidity, before + (after - before) / 4);
+    }
+
+    #[test]
+    fn observe_before_oldest_observation_fails() {
+        let observation_state = three_observations();
+        assert_eq!(
+            observation_state.observe(1040, 41, 20, 100).unwrap_err(),
+            ErrorCode::ObservationTooOld.into()
+        );
+        assert_eq!(
+            observation_state.observe(1040, 1041, 20, 100).unwrap_err(),
+            ErrorCode::ObservationTooOld.into()
+        );
+    }
+
+    #[test]
+    fn observe_across_wrapped_buffer() {
+        // tick 1 throughout, so tick_cumulative is the seconds since 1000
+        let mut observation_state = new_observation_state(1000);
+        let updates = OBSERVATION_NUM + 5;
+        for i in 1..=updates {
+            observation_state.update(1000 + i * 10, 1, 100);
+        }
+        assert_eq!(observation_state.observation_index as u32, updates % OBSERVATION_NUM);
+
+        // the oldest surviving observation is the one written after the latest slot
+        let latest = 1000 + updates * 10;
+        let oldest = 1000 + (updates + 1 - OBSERVATION_NUM) * 10;
+        for target in oldest..=latest {
+            assert_eq!(
+                observation_state.observe(latest, latest - target, 1, 100).unwrap().0,
+                i64::from(target - 1000)
+            );
+        }
+        assert_eq!(
+            observation_state.observe(latest, latest - oldest + 1, 1, 100).unwrap_err(),
+            ErrorCode::ObservationTooOld.into()
+        );
+    }
+
+    #[test]
+    fn twap_tick_rounds_towards_negative_infinity() {
+        let observation_state = three_observations();
+        // (200 - 100) / 30
+        assert_eq!(observation_state.get_twap_tick(1040, 30, 20, 100).unwrap(), 3);
+        // (0 - 100) / 20
+        assert_eq!(observation_state.get_twap_tick(1030, 20, -5, 100).unwrap(), -5);
+        // (0 - 80) / 22
+        assert_eq!(observation_state.get_twap_tick(1030, 22, 20, 100).unwrap(), -4);
+    }
+
+    #[test]
+    fn twap_tick_rejects_zero_window() {
+        let observation_state = three_observations();
+        assert_eq!(
+            observation_state.get_twap_tick(1040, 0, 20, 100).unwrap_err(),
+            ErrorCode::InvalidTwapWindow.into()
+        );
+    }
+}
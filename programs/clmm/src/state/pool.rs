@@ -1,13 +1,65 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::Mint;
 
-use crate::constants::POOL_SEED;
+use crate::constants::{
+    DYNAMIC_FEE_CONTROL, DYNAMIC_FEE_DECAY_PERIOD, DYNAMIC_FEE_FILTER_PERIOD, DYNAMIC_FEE_REDUCTION_FACTOR,
+    FEE_RATE_DENOMINATOR_VALUE, POOL_SEED, REWARD_NUM,
+};
+use crate::libraries::big_num::U128;
+use crate::libraries::fixed_point_64;
+use crate::libraries::full_math::MulDiv;
+
+use crate::error::ErrorCode;
+
+use super::AmmConfig;
+
+/// A reward token emitted to in-range liquidity between open_time and end_time
+#[zero_copy]
+#[repr(C, packed)]
+#[derive(Default, Debug)]
+pub struct RewardInfo {
+    pub token_mint: Pubkey,
+    pub token_vault: Pubkey,
+    pub open_time: u64,
+    pub end_time: u64,
+    pub last_update_time: u64,
+    /// Q64.64 amount of reward token emitted per second
+    pub emissions_per_second_x64: u128,
+    /// Q64.64 amount of reward token emitted per unit of liquidity since the reward was initialized
+    pub reward_growth_global_x64: u128,
+}
+
+impl RewardInfo {
+    pub const LEN: usize =
+        32 +
+        32 +
+        8 +
+        8 +
+        8 +
+        16 +
+        16;
+
+    pub fn initialized(&self) -> bool {
+        self.token_mint != Pubkey::default()
+    }
+
+    /// The reward tokens emitted over duration seconds, rounded up so that funding always covers them
+    pub fn get_reward_amount(emissions_per_second_x64: u128, duration: u64) -> Result<u64> {
+        let amount = U128::from(duration)
+            .mul_div_ceil(U128::from(emissions_per_second_x64), U128::from(fixed_point_64::Q64))
+            .ok_or(ErrorCode::InvalidRewardParams)?;
+        require!(amount <= U128::from(u64::MAX), ErrorCode::InvalidRewardParams);
+        Ok(amount.as_u64())
+    }
+}
 
 #[account(zero_copy)]
 #[repr(C, packed)]
 #[derive(Default, Debug)]
 pub struct PoolState {
     pub bump: [u8; 1],
+    /// The fee tier the pool was created with
+    pub amm_config: Pubkey,
     pub token_decimals_0: u8,
     pub token_decimals_1: u8,
     pub tick_spacing: u16,
@@ -18,8 +70,19 @@ pub struct PoolState {
     pub token_vault_1: Pubkey,
     pub fee_rate: u32, // units of hundredths of a basis point(0.0001%)
     pub protocol_fee_rate: u32,
+    /// Cap on the dynamic fee taken from the fee tier, zero when dynamic fees are off
+    pub max_fee_rate: u32,
+    /// The tick volatility is measured from, reset when swaps are DYNAMIC_FEE_FILTER_PERIOD apart
+    pub tick_reference: i32,
+    /// Volatility carried over from earlier swaps, in tick spacings
+    pub volatility_reference: u32,
+    /// volatility_reference plus the distance from tick_reference, in tick spacings
+    pub volatility_accumulator: u32,
+    pub volatility_update_time: u64,
 
     pub tick_array_bitmap: Pubkey,
+    /// The ObservationState holding the pool's price history
+    pub observation_key: Pubkey,
 
     pub liquidity: u128,
     /// sqrt(token_1/token_0), Q64.64 value
@@ -28,10 +91,20 @@ pub struct PoolState {
     pub fee_growth_global_1_x64: u128,
     pub protocol_fees_0: u128,
     pub protocol_fees_1: u128,
+    /// The last epoch id handed out to a batch of limit orders on one of the pool's ticks
+    pub limit_order_epoch: u64,
+
+    pub reward_infos: [RewardInfo; REWARD_NUM as usize],
 }
 
+// initialize_pool allocates LEN, which must cover every field of the packed layout
+const _: () = assert!(PoolState::LEN == std::mem::size_of::<PoolState>());
+
 impl PoolState {
     pub const LEN: usize =
+        1 +
+        32 +
+        1 +
         1 +
         2 +
         4 +
@@ -41,17 +114,26 @@ impl PoolState {
         32 +
         4 +
         4 +
+        4 +
+        4 +
+        4 +
+        4 +
+        8 +
+        32 +
         32 +
         16 +
         16 +
         16 +
         16 +
         16 +
-        16;
+        16 +
+        8 +
+        RewardInfo::LEN * REWARD_NUM as usize;
 
-    pub fn seeds(&self) -> [&[u8]; 4] {
+    pub fn seeds(&self) -> [&[u8]; 5] {
         [
             &POOL_SEED.as_bytes(),
+            self.amm_config.as_ref(),
             self.token_0.as_ref(),
             self.token_1.as_ref(),
             self.bump.as_ref(),
@@ -65,37 +147,323 @@ impl PoolState {
     pub fn initialize(
         &mut self,
         bump: u8,
-        tick_spacing: u16,
-        fee_rate: u32,
-        protocol_fee_rate: u32,
+        amm_config: &Account<AmmConfig>,
         sqrt_price_x64: u128,
         tick: i32,
         token_vault_0: Pubkey,
         token_vault_1: Pubkey,
         token_0: &InterfaceAccount<Mint>,
         token_1: &InterfaceAccount<Mint>,
-        bitmap: Pubkey
+        bitmap: Pubkey,
+        observation_key: Pubkey,
     ) -> Result<()> {
         self.bump = [bump];
-        self.fee_rate = fee_rate;
-        self.protocol_fee_rate = protocol_fee_rate;
+        self.amm_config = amm_config.key();
+        self.fee_rate = amm_config.trade_fee_rate;
+        self.protocol_fee_rate = amm_config.protocol_fee_rate;
+        self.max_fee_rate = amm_config.max_dynamic_fee_rate;
+        self.tick_reference = tick;
         self.token_0 = token_0.key();
         self.token_1 = token_1.key();
         self.token_decimals_0 = token_0.decimals;
         self.token_decimals_1 = token_1.decimals;
         self.token_vault_0 = token_vault_0;
         self.token_vault_1 = token_vault_1;
-        self.tick_spacing = tick_spacing;
+        self.tick_spacing = amm_config.tick_spacing;
         self.tick_current = tick;
         self.sqrt_price_x64 = sqrt_price_x64;
 
         self.tick_array_bitmap = bitmap;
+        self.observation_key = observation_key;
 
         self.fee_growth_global_0_x64 = 0;
         self.fee_growth_global_1_x64 = 0;
         self.protocol_fees_0 = 0;
         self.protocol_fees_1 = 0;
+        self.limit_order_epoch = 0;
         self.liquidity = 0;
+        self.reward_infos = [RewardInfo::default(); REWARD_NUM as usize];
         Ok(())
     }
-}
\ No newline at end of file
+
+    /// Accrues the emissions since the last update into reward_growth_global_x64.
+    /// Must be called before the pool's liquidity changes. Nothing accrues while
+    /// there is no liquidity in range.
+    pub fn update_reward_infos(&mut self, curr_timestamp: u64) -> Result<()> {
+        let liquidity = self.liquidity;
+        for i in 0..REWARD_NUM as usize {
+            let mut reward_info = self.reward_infos[i];
+            if !reward_info.initialized() {
+                continue;
+            }
+            let start = reward_info.last_update_time.max(reward_info.open_time);
+            let end = curr_timestamp.min(reward_info.end_time);
+            if liquidity > 0 && end > start {
+                let growth_delta = U128::from(end - start)
+                    .mul_div_floor(U128::from(reward_info.emissions_per_second_x64), U128::from(liquidity))
+                    .ok_or(ErrorCode::RewardGrowthOverflow)?
+                    .as_u128();
+                reward_info.reward_growth_global_x64 =
+                    reward_info.reward_growth_global_x64.wrapping_add(growth_delta);
+            }
+            reward_info.last_update_time = curr_timestamp.max(reward_info.last_update_time);
+            self.reward_infos[i] = reward_info;
+        }
+        Ok(())
+    }
+
+    /// Called at the start of a swap: keeps the volatility reference for swaps in quick
+    /// succession, decays it after DYNAMIC_FEE_FILTER_PERIOD and drops it after DYNAMIC_FEE_DECAY_PERIOD
+    pub fn update_volatility_reference(&mut self, curr_timestamp: u64) {
+        if self.max_fee_rate == 0 {
+            return;
+        }
+        let elapsed = curr_timestamp.saturating_sub(self.volatility_update_time);
+        if elapsed >= DYNAMIC_FEE_FILTER_PERIOD {
+            self.tick_reference = self.tick_current;
+            self.volatility_reference = if elapsed < DYNAMIC_FEE_DECAY_PERIOD {
+                (u64::from(self.volatility_accumulator) * u64::from(DYNAMIC_FEE_REDUCTION_FACTOR)
+                    / u64::from(FEE_RATE_DENOMINATOR_VALUE)) as u32
+            } else {
+                0
+            };
+        }
+        self.volatility_update_time = curr_timestamp;
+    }
+
+    /// Sets volatility_accumulator for a swap step starting at tick and returns the fee rate
+    /// the step pays. In dynamic-fee mode this is fee_rate plus DYNAMIC_FEE_CONTROL per tick
+    /// of accumulated volatility, capped at max_fee_rate.
+    pub fn update_volatility_and_fee_rate(&mut self, tick: i32) -> u32 {
+        if self.max_fee_rate == 0 {
+            return self.fee_rate;
+        }
+        self.volatility_accumulator = self
+            .volatility_reference
+            .saturating_add(self.tick_spacings_from_reference(tick));
+        self.get_dynamic_fee_rate()
+    }
+
+    /// The tick a swap step starting at tick has to stop at, so that the fee set by
+    /// update_volatility_and_fee_rate holds for every tick the step covers. Below the cap
+    /// that is the edge of the tick spacing holding tick. At the cap it is where the price,
+    /// heading back towards tick_reference, brings the fee below the cap again, or None
+    /// if the fee can no longer change in the swap direction.
+    pub fn get_fee_rate_bound_tick(&self, tick: i32, zero_for_one: bool) -> Option<i32> {
+        if self.max_fee_rate == 0 {
+            return None;
+        }
+        let tick_spacing = i32::from(self.tick_spacing);
+        let spacing = tick.div_euclid(tick_spacing);
+        let reference_spacing = self.tick_reference.div_euclid(tick_spacing);
+        let capped_volatility =
+            Self::get_capped_volatility(self.tick_spacing, self.fee_rate, self.max_fee_rate);
+        let volatility = u64::from(self.volatility_reference) + u64::from(spacing.abs_diff(reference_spacing));
+
+        let bound_spacing = if volatility < capped_volatility {
+            spacing
+        } else {
+            let towards_reference = if zero_for_one {
+                spacing > reference_spacing
+            } else {
+                spacing < reference_spacing
+            };
+            if !towards_reference {
+                return None;
+            }
+            // the first spacing on the way whose volatility is below the cap
+            let distance = capped_volatility.checked_sub(u64::from(self.volatility_reference) + 1)? as i32;
+            if zero_for_one {
+                reference_spacing + distance + 1
+            } else {
+                reference_spacing - distance - 1
+            }
+        };
+        Some(if zero_for_one {
+            bound_spacing * tick_spacing
+        } else {
+            (bound_spacing + 1) * tick_spacing
+        })
+    }
+
+    /// The volatility, in tick spacings, from which the dynamic fee sits at max_fee_rate
+    pub fn get_capped_volatility(tick_spacing: u16, fee_rate: u32, max_fee_rate: u32) -> u64 {
+        let fee_rate_per_spacing = u64::from(tick_spacing) * u64::from(DYNAMIC_FEE_CONTROL);
+        u64::from(max_fee_rate.saturating_sub(fee_rate)).div_ceil(fee_rate_per_spacing)
+    }
+
+    /// Whole tick spacings between the one holding tick and the one holding tick_reference
+    fn tick_spacings_from_reference(&self, tick: i32) -> u32 {
+        let tick_spacing = i32::from(self.tick_spacing);
+        tick.div_euclid(tick_spacing)
+            .abs_diff(self.tick_reference.div_euclid(tick_spacing))
+    }
+
+    fn get_dynamic_fee_rate(&self) -> u32 {
+        let variable_fee_rate = u64::from(self.volatility_accumulator)
+            * u64::from(self.tick_spacing)
+            * u64::from(DYNAMIC_FEE_CONTROL);
+        (u64::from(self.fee_rate) + variable_fee_rate).min(u64::from(self.max_fee_rate)) as u32
+    }
+
+    pub fn reward_growths_global(&self) -> [u128; REWARD_NUM as usize] {
+        let reward_infos = self.reward_infos;
+        reward_infos.map(|reward_info| reward_info.reward_growth_global_x64)
+    }
+}
+#[cfg(test)]
+mod pool_test {
+    use super::*;
+
+    fn dynamic_fee_pool(fee_rate: u32, max_fee_rate: u32) -> PoolState {
+        PoolState {
+            tick_spacing: 10,
+            fee_rate,
+            max_fee_rate,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn fee_rate_passes_through_without_dynamic_fees() {
+        let mut pool_state = dynamic_fee_pool(3000, 0);
+        assert_eq!(pool_state.update_volatility_and_fee_rate(10_000), 3000);
+        assert_eq!({ pool_state.volatility_accumulator }, 0);
+        assert_eq!(pool_state.get_fee_rate_bound_tick(10_000, true), None);
+
+        pool_state.update_volatility_reference(1000);
+        assert_eq!({ pool_state.volatility_update_time }, 0);
+    }
+
+    #[test]
+    fn fee_rate_grows_with_tick_spacings_moved() {
+        let mut pool_state = dynamic_fee_pool(3000, 100_000);
+        let fee_per_spacing = 10 * DYNAMIC_FEE_CONTROL;
+        assert_eq!(pool_state.update_volatility_and_fee_rate(5), 3000);
+        assert_eq!(pool_state.update_volatility_and_fee_rate(25), 3000 + 2 * fee_per_spacing);
+        assert_eq!({ pool_state.volatility_accumulator }, 2);
+        assert_eq!(pool_state.update_volatility_and_fee_rate(-1), 3000 + fee_per_spacing);
+
+        pool_state.volatility_reference = 4;
+        assert_eq!(pool_state.update_volatility_and_fee_rate(25), 3000 + 6 * fee_per_spacing);
+    }
+
+    #[test]
+    fn fee_rate_capped_at_max_fee_rate() {
+        let mut pool_state = dynamic_fee_pool(3000, 3500);
+        assert_eq!(pool_state.update_volatility_and_fee_rate(10_000), 3500);
+        pool_state.volatility_reference = u32::MAX;
+        assert_eq!(pool_state.update_volatility_and_fee_rate(10_000), 3500);
+    }
+
+    #[test]
+    fn steps_stop_at_tick_spacing_edges_while_the_fee_can_change() {
+        let mut pool_state = dynamic_fee_pool(3000, 100_000);
+        pool_state.update_volatility_and_fee_rate(25);
+        assert_eq!(pool_state.get_fee_rate_bound_tick(25, false), Some(30));
+        assert_eq!(pool_state.get_fee_rate_bound_tick(25, true), Some(20));
+        pool_state.update_volatility_and_fee_rate(-5);
+        assert_eq!(pool_state.get_fee_rate_bound_tick(-5, false), Some(0));
+        assert_eq!(pool_state.get_fee_rate_bound_tick(-5, true), Some(-10));
+
+        // at the cap only moving back towards tick_reference can lower the fee
+        pool_state.max_fee_rate = 3100;
+        pool_state.update_volatility_and_fee_rate(25);
+        assert_eq!(pool_state.get_fee_rate_bound_tick(25, false), None);
+        assert_eq!(pool_state.get_fee_rate_bound_tick(25, true), Some(10));
+    }
+
+    #[test]
+    fn capped_steps_skip_ahead_to_where_the_fee_drops() {
+        // the fee reaches its cap of 3500 at 5 spacings of volatility
+        let mut pool_state = dynamic_fee_pool(3000, 3500);
+        assert_eq!(PoolState::get_capped_volatility(10, 3000, 3500), 5);
+        assert_eq!(pool_state.get_fee_rate_bound_tick(125, true), Some(50));
+        assert_eq!(pool_state.get_fee_rate_bound_tick(-125, false), Some(-40));
+        assert_eq!(pool_state.get_fee_rate_bound_tick(45, true), Some(40));
+
+        // carried over volatility alone keeps the fee at the cap
+        pool_state.volatility_reference = 5;
+        assert_eq!(pool_state.get_fee_rate_bound_tick(125, true), None);
+    }
+
+    #[test]
+    fn volatility_reference_kept_within_filter_period() {
+        let mut pool_state = dynamic_fee_pool(3000, 100_000);
+        pool_state.volatility_update_time = 1000;
+        pool_state.volatility_reference = 2;
+        pool_state.volatility_accumulator = 7;
+        pool_state.tick_current = 50;
+
+        pool_state.update_volatility_reference(1000 + DYNAMIC_FEE_FILTER_PERIOD - 1);
+        assert_eq!({ pool_state.volatility_reference }, 2);
+        assert_eq!({ pool_state.tick_reference }, 0);
+        assert_eq!({ pool_state.volatility_update_time }, 1000 + DYNAMIC_FEE_FILTER_PERIOD - 1);
+    }
+
+    #[test]
+    fn volatility_reference_decays_after_filter_period() {
+        let mut pool_state = dynamic_fee_pool(3000, 100_000);
+        pool_state.volatility_update_time = 1000;
+        pool_state.volatility_reference = 2;
+        pool_state.volatility_accumulator = 7;
+        pool_state.tick_current = 50;
+
+        pool_state.update_volatility_reference(1000 + DYNAMIC_FEE_FILTER_PERIOD);
+        assert_eq!(
+            { pool_state.volatility_reference },
+            7 * DYNAMIC_FEE_REDUCTION_FACTOR / FEE_RATE_DENOMINATOR_VALUE
+        );
+        assert_eq!({ pool_state.tick_reference }, 50);
+
+        pool_state.volatility_accumulator = 7;
+        pool_state.update_volatility_reference(1000 + DYNAMIC_FEE_FILTER_PERIOD + DYNAMIC_FEE_DECAY_PERIOD);
+        assert_eq!({ pool_state.volatility_reference }, 0);
+    }
+
+    mod fuzz_tests {
+        use super::*;
+        use crate::constants::DYNAMIC_FEE_MAX_SPACINGS;
+        use proptest::prelude::*;
+
+        proptest! {
+            #[test]
+            fn fee_rate_bound_tick_test (
+                tick_spacing in 1..200u16,
+                spacings_to_cap in 1..=DYNAMIC_FEE_MAX_SPACINGS,
+                tick_reference in -10_000..10_000i32,
+                volatility_reference in 0..40u32,
+                tick_start in -10_000..10_000i32,
+                zero_for_one in proptest::bool::ANY,
+            ) {
+                let fee_rate_per_spacing = u32::from(tick_spacing) * DYNAMIC_FEE_CONTROL;
+                let mut pool_state = PoolState {
+                    tick_spacing,
+                    fee_rate: 3000,
+                    max_fee_rate: 3000 + spacings_to_cap * fee_rate_per_spacing,
+                    tick_reference,
+                    volatility_reference,
+                    ..Default::default()
+                };
+
+                // walk the price far in the swap direction, one bounded step at a time
+                let tick_end = if zero_for_one { tick_start - 100_000 } else { tick_start + 100_000 };
+                let mut tick = tick_start;
+                let mut steps = 0;
+                while let Some(bound_tick) = pool_state.get_fee_rate_bound_tick(tick, zero_for_one) {
+                    if (zero_for_one && bound_tick <= tick_end) || (!zero_for_one && bound_tick >= tick_end) {
+                        break;
+                    }
+                    // the fee holds over every tick the step covers
+                    let fee_rate = pool_state.update_volatility_and_fee_rate(tick);
+                    let last_tick = if zero_for_one { bound_tick } else { bound_tick - 1 };
+                    assert_eq!(pool_state.update_volatility_and_fee_rate(last_tick), fee_rate);
+
+                    tick = if zero_for_one { bound_tick - 1 } else { bound_tick };
+                    steps += 1;
+                }
+                assert!(steps <= 2 * spacings_to_cap + 2);
+            }
+        }
+    }
+}
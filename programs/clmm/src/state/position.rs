@@ -0,0 +1,111 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::REWARD_NUM;
+use crate::libraries::big_num::U128;
+use crate::libraries::fixed_point_64;
+use crate::libraries::full_math::MulDiv;
+use crate::libraries::liquidity_math;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug)]
+pub struct PositionRewardInfo {
+    /// Reward growth per unit of liquidity inside the position's range as of the last update
+    pub growth_inside_last_x64: u128,
+    pub reward_amount_owed: u64,
+}
+
+impl PositionRewardInfo {
+    pub const LEN: usize =
+        16 +
+        8;
+}
+
+#[account]
+#[derive(Default, Debug)]
+pub struct PositionState {
+    pub pool_id: Pubkey,
+    /// The position belongs to whoever holds the NFT of this mint. It stands in for an
+    /// owner wallet key: the NFT can be transferred, and a stored wallet would go stale.
+    pub nft_mint: Pubkey,
+    pub tick_lower_index: i32,
+    pub tick_upper_index: i32,
+    pub liquidity: u128,
+    /// Fee growth per unit of liquidity inside the position's range as of the last update
+    pub fee_growth_inside_0_last_x64: u128,
+    pub fee_growth_inside_1_last_x64: u128,
+    /// Fees owed to the position owner, settled on every liquidity change
+    pub tokens_owed_0: u64,
+    pub tokens_owed_1: u64,
+    pub reward_infos: [PositionRewardInfo; REWARD_NUM as usize],
+}
+
+impl PositionState {
+    pub const LEN: usize =
+        32 +
+        32 +
+        4 +
+        4 +
+        16 +
+        16 +
+        16 +
+        8 +
+        8 +
+        PositionRewardInfo::LEN * REWARD_NUM as usize;
+
+    pub fn initialize(&mut self, pool_id: Pubkey, nft_mint: Pubkey, tick_lower_index: i32, tick_upper_index: i32) {
+        self.pool_id = pool_id;
+        self.nft_mint = nft_mint;
+        self.tick_lower_index = tick_lower_index;
+        self.tick_upper_index = tick_upper_index;
+    }
+
+    /// Credits the fees accrued since the last update to tokens_owed,
+    /// then applies liquidity_delta
+    pub fn update(
+        &mut self,
+        liquidity_delta: i128,
+        fee_growth_inside_0_x64: u128,
+        fee_growth_inside_1_x64: u128,
+    ) -> Result<()> {
+        let tokens_owed_0 = Self::calculate_amount_owed(
+            fee_growth_inside_0_x64,
+            self.fee_growth_inside_0_last_x64,
+            self.liquidity,
+        );
+        let tokens_owed_1 = Self::calculate_amount_owed(
+            fee_growth_inside_1_x64,
+            self.fee_growth_inside_1_last_x64,
+            self.liquidity,
+        );
+
+        if liquidity_delta != 0 {
+            self.liquidity = liquidity_math::add_delta(self.liquidity, liquidity_delta)?;
+        }
+        self.fee_growth_inside_0_last_x64 = fee_growth_inside_0_x64;
+        self.fee_growth_inside_1_last_x64 = fee_growth_inside_1_x64;
+        self.tokens_owed_0 = self.tokens_owed_0.checked_add(tokens_owed_0).unwrap();
+        self.tokens_owed_1 = self.tokens_owed_1.checked_add(tokens_owed_1).unwrap();
+        Ok(())
+    }
+
+    /// Credits the rewards accrued since the last update to reward_amount_owed.
+    /// Must be called before the position's liquidity changes.
+    pub fn update_rewards(&mut self, reward_growths_inside_x64: [u128; REWARD_NUM as usize]) -> Result<()> {
+        for (reward_info, reward_growth_inside_x64) in self.reward_infos.iter_mut().zip(reward_growths_inside_x64) {
+            let amount_owed = Self::calculate_amount_owed(
+                reward_growth_inside_x64,
+                reward_info.growth_inside_last_x64,
+                self.liquidity,
+            );
+            reward_info.growth_inside_last_x64 = reward_growth_inside_x64;
+            reward_info.reward_amount_owed = reward_info.reward_amount_owed.checked_add(amount_owed).unwrap();
+        }
+        Ok(())
+    }
+
+    fn calculate_amount_owed(fee_growth_inside_x64: u128, fee_growth_inside_last_x64: u128, liquidity: u128) -> u64 {
+        U128::from(fee_growth_inside_x64.wrapping_sub(fee_growth_inside_last_x64))
+            .mul_div_floor(U128::from(liquidity), U128::from(fixed_point_64::Q64))
+            .unwrap()
+            .to_underflow_u64()
+    }
+}
@@ -0,0 +1,16 @@
+use anchor_lang::prelude::*;
+
+/// Created by the admin to let pools and rewards be created for a mint whose
+/// Token-2022 extensions would otherwise be rejected
+#[account]
+#[derive(Default, Debug)]
+pub struct SupportMintAssociated {
+    pub bump: u8,
+    pub mint: Pubkey,
+}
+
+impl SupportMintAssociated {
+    pub const LEN: usize =
+        1 +
+        32;
+}
@@ -1,16 +1,21 @@
+use std::ops::RangeInclusive;
+
 use anchor_lang::zero_copy;
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::program::invoke_signed;
 use anchor_lang::solana_program::system_instruction;
 
 use crate::constants::ANCHOR_SIZE;
+use crate::constants::REWARD_NUM;
 use crate::constants::TICK_ARRAY_SEED;
+use crate::libraries::full_math::MulDiv;
 use crate::libraries::liquidity_math;
+use crate::libraries::tick_array_bitmap_math;
 use crate::util::AccountLoad;
 use crate::error::ErrorCode;
-use crate::{constants::TICK_ARRAY_BITMAP_SIZE, constants::TICK_ARRAY_SIZE};
+use crate::{constants::TICK_ARRAY_BITMAP_SIZE, constants::TICK_ARRAY_SIZE, constants::TICK_MAX};
 
-use super::PoolState;
+use super::{Observation, PoolState, RewardInfo};
 
 #[macro_export]
 macro_rules! tick_index_check{
@@ -34,9 +39,25 @@ pub struct TickState {
     pub liquidity_gross: u128,
     pub fee_growth_outside_0_x64: u128,
     pub fee_growth_outside_1_x64: u128,
-    // pub seconds_outside: U256,
-    // pub tick_cumulative_outside: U256,
-    // pub seconds_per_liquidity_outside_x128: U256,
+    /// The seconds spent on the other side of the tick, relative to the current tick.
+    /// Only meaningful relative to another tick's value, like the fee growths.
+    pub seconds_per_liquidity_outside_x64: u128,
+    pub tick_cumulative_outside: i64,
+    pub seconds_outside: u32,
+    pub reward_growths_outside_x64: [u128; REWARD_NUM as usize],
+    /// Epoch id of the batch of limit orders resting on the tick, 0 if there is none.
+    /// A limit order whose epoch no longer matches has been filled in full.
+    pub order_epoch: u64,
+    /// Shares of the batch, each worth order_remaining / order_shares of the unfilled
+    /// amount. Orders joining a partly filled batch get proportionally more shares.
+    pub order_shares: u64,
+    /// The unfilled amount of the batch, in the token it sells
+    pub order_remaining: u64,
+    /// 1 if the batch sells token_1 and is filled by zero_for_one swaps, 0 if it sells token_0
+    pub order_zero_for_one: u8,
+    /// How many bits order_shares has been shifted down to stay in range. The shares of
+    /// an order that joined at a lower scale count shifted down by the difference.
+    pub order_share_scale: u32,
 }
 
 impl TickState {
@@ -45,13 +66,21 @@ impl TickState {
         16 +
         16 +
         16 +
-        // 32 +
-        // 32 +
-        // 32
-        0 ;
+        16 +
+        8 +
+        4 +
+        16 * REWARD_NUM as usize +
+        8 +
+        8 +
+        8 +
+        1 +
+        4;
+
+    /// snapshot holds the pool's oracle accumulators as of now
     pub fn update(
         &mut self,
         pool_state: &PoolState,
+        snapshot: &Observation,
         tick_index: i32, 
         liquidity_delta: i128,
         is_upper: bool,
@@ -59,10 +88,7 @@ impl TickState {
         let is_initializing = !self.valid();
         if is_initializing {
             require!(liquidity_delta > 0, ErrorCode::InitializeTickWithZeroOrNegLiquidity);
-            if pool_state.tick_current >= tick_index {
-                self.fee_growth_outside_0_x64 = pool_state.fee_growth_global_0_x64;
-                self.fee_growth_outside_1_x64 = pool_state.fee_growth_global_1_x64;
-            }
+            self.initialize_outside(pool_state, snapshot, tick_index);
         }
         self.liquidity_gross = liquidity_math::add_delta(self.liquidity_gross, liquidity_delta)?;
         if is_upper {
@@ -77,11 +103,221 @@ impl TickState {
         Ok(is_initializing)
     }
 
+    /// By convention, we assume that all growth before a tick was initialized happened below the tick
+    fn initialize_outside(&mut self, pool_state: &PoolState, snapshot: &Observation, tick_index: i32) {
+        if pool_state.tick_current >= tick_index {
+            self.fee_growth_outside_0_x64 = pool_state.fee_growth_global_0_x64;
+            self.fee_growth_outside_1_x64 = pool_state.fee_growth_global_1_x64;
+            self.seconds_per_liquidity_outside_x64 = snapshot.seconds_per_liquidity_cumulative_x64;
+            self.tick_cumulative_outside = snapshot.tick_cumulative;
+            self.seconds_outside = snapshot.block_timestamp;
+            self.reward_growths_outside_x64 = pool_state.reward_growths_global();
+        }
+    }
+
+    /// Adds a limit order of amount to the tick, opening a new batch if there is none.
+    /// Returns the (epoch, shares, share_scale) of the order.
+    pub fn add_order(
+        &mut self,
+        pool_state: &mut PoolState,
+        snapshot: &Observation,
+        tick_index: i32,
+        zero_for_one: bool,
+        amount: u64,
+    ) -> Result<(u64, u64, u32)> {
+        if !self.valid() {
+            self.initialize_outside(pool_state, snapshot, tick_index);
+        }
+        if self.order_shares == 0 {
+            pool_state.limit_order_epoch += 1;
+            self.order_epoch = pool_state.limit_order_epoch;
+            self.order_shares = amount;
+            self.order_remaining = amount;
+            self.order_zero_for_one = u8::from(zero_for_one);
+            self.order_share_scale = 0;
+            return Ok((self.order_epoch, amount, 0));
+        }
+        require!(
+            self.order_zero_for_one == u8::from(zero_for_one),
+            ErrorCode::LimitOrderSideConflict
+        );
+
+        // Shares get dearer in tokens as the batch fills. Once the batch is filled
+        // down to dust a join would overflow u64, so all shares are shifted down instead.
+        let shares = u128::from(amount) * u128::from(self.order_shares) / u128::from(self.order_remaining);
+        let shares_total = shares + u128::from(self.order_shares);
+        let shift = (u128::BITS - shares_total.leading_zeros()).saturating_sub(u64::BITS);
+        let shares = (shares >> shift) as u64;
+        require!(shares > 0, ErrorCode::LimitOrderTooSmall);
+        self.order_share_scale = self
+            .order_share_scale
+            .checked_add(shift)
+            .ok_or(ErrorCode::LimitOrderSharesOverflow)?;
+        self.order_shares = (self.order_shares >> shift) + shares;
+        self.order_remaining = self
+            .order_remaining
+            .checked_add(amount)
+            .ok_or(ErrorCode::MaxTokenOverflow)?;
+        Ok((self.order_epoch, shares, self.order_share_scale))
+    }
+
+    /// The unfilled amount of an order in the current batch
+    pub fn get_order_remaining(&self, shares: u64, share_scale: u32, round_up: bool) -> u64 {
+        let shares = self.get_order_shares(shares, share_scale);
+        if round_up {
+            shares.mul_div_ceil(self.order_remaining, self.order_shares).unwrap()
+        } else {
+            shares.mul_div_floor(self.order_remaining, self.order_shares).unwrap()
+        }
+    }
+
+    /// Takes an order out of the current batch and returns its unfilled amount, at most
+    /// unfilled_max. Rounding dust beyond that stays with the batch.
+    pub fn remove_order(&mut self, shares: u64, share_scale: u32, unfilled_max: u64) -> u64 {
+        let unfilled = self
+            .get_order_remaining(shares, share_scale, false)
+            .min(unfilled_max);
+        self.order_shares -= self.get_order_shares(shares, share_scale);
+        self.order_remaining -= unfilled;
+        // rounding may leave dust that no order owns
+        if self.order_shares == 0 || self.order_remaining == 0 {
+            self.clear_orders();
+        }
+        unfilled
+    }
+
+    /// Fills amount of the resting orders, closing the batch once it is used up
+    pub fn fill_orders(&mut self, amount: u64) {
+        self.order_remaining -= amount;
+        if self.order_remaining == 0 {
+            self.clear_orders();
+        }
+    }
+
+    /// Whether the tick holds unfilled limit orders that a swap in this direction takes
+    pub fn has_orders_for(&self, zero_for_one: bool) -> bool {
+        self.order_remaining > 0 && self.order_zero_for_one == u8::from(zero_for_one)
+    }
+
+    /// An order's shares at the batch's current scale
+    fn get_order_shares(&self, shares: u64, share_scale: u32) -> u64 {
+        shares
+            .checked_shr(self.order_share_scale - share_scale)
+            .unwrap_or(0)
+    }
+
+    fn clear_orders(&mut self) {
+        self.order_epoch = 0;
+        self.order_shares = 0;
+        self.order_remaining = 0;
+        self.order_zero_for_one = 0;
+        self.order_share_scale = 0;
+    }
+
+    /// Transitions to the tick as needed by price movement, returning the
+    /// liquidity_net to apply when crossing left to right
+    pub fn cross(
+        &mut self,
+        fee_growth_global_0_x64: u128,
+        fee_growth_global_1_x64: u128,
+        snapshot: &Observation,
+        reward_growths_global_x64: &[u128; REWARD_NUM as usize],
+    ) -> i128 {
+        self.fee_growth_outside_0_x64 =
+            fee_growth_global_0_x64.wrapping_sub(self.fee_growth_outside_0_x64);
+        self.fee_growth_outside_1_x64 =
+            fee_growth_global_1_x64.wrapping_sub(self.fee_growth_outside_1_x64);
+        self.seconds_per_liquidity_outside_x64 = snapshot
+            .seconds_per_liquidity_cumulative_x64
+            .wrapping_sub(self.seconds_per_liquidity_outside_x64);
+        self.tick_cumulative_outside = snapshot.tick_cumulative.wrapping_sub(self.tick_cumulative_outside);
+        self.seconds_outside = snapshot.block_timestamp.wrapping_sub(self.seconds_outside);
+        let mut reward_growths_outside_x64 = self.reward_growths_outside_x64;
+        for (outside, global) in reward_growths_outside_x64.iter_mut().zip(reward_growths_global_x64) {
+            *outside = global.wrapping_sub(*outside);
+        }
+        self.reward_growths_outside_x64 = reward_growths_outside_x64;
+        self.liquidity_net
+    }
+
     pub fn valid(&self) -> bool {
-        self.liquidity_gross != 0
+        self.liquidity_gross != 0 || self.order_shares != 0
     }
 }
 
+/// Retrieves the all time fee growth data in token_0 and token_1, per unit of liquidity,
+/// inside a position's tick boundaries
+pub fn get_fee_growth_inside(
+    tick_lower: &TickState,
+    tick_upper: &TickState,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+    tick_current: i32,
+    fee_growth_global_0_x64: u128,
+    fee_growth_global_1_x64: u128,
+) -> (u128, u128) {
+    // calculate fee growth below
+    let (fee_growth_below_0_x64, fee_growth_below_1_x64) = if tick_current >= tick_lower_index {
+        (tick_lower.fee_growth_outside_0_x64, tick_lower.fee_growth_outside_1_x64)
+    } else {
+        (
+            fee_growth_global_0_x64.wrapping_sub(tick_lower.fee_growth_outside_0_x64),
+            fee_growth_global_1_x64.wrapping_sub(tick_lower.fee_growth_outside_1_x64),
+        )
+    };
+
+    // calculate fee growth above
+    let (fee_growth_above_0_x64, fee_growth_above_1_x64) = if tick_current < tick_upper_index {
+        (tick_upper.fee_growth_outside_0_x64, tick_upper.fee_growth_outside_1_x64)
+    } else {
+        (
+            fee_growth_global_0_x64.wrapping_sub(tick_upper.fee_growth_outside_0_x64),
+            fee_growth_global_1_x64.wrapping_sub(tick_upper.fee_growth_outside_1_x64),
+        )
+    };
+
+    (
+        fee_growth_global_0_x64
+            .wrapping_sub(fee_growth_below_0_x64)
+            .wrapping_sub(fee_growth_above_0_x64),
+        fee_growth_global_1_x64
+            .wrapping_sub(fee_growth_below_1_x64)
+            .wrapping_sub(fee_growth_above_1_x64),
+    )
+}
+
+/// Retrieves the reward growth per unit of liquidity inside a position's tick boundaries,
+/// for each reward slot of the pool
+pub fn get_reward_growths_inside(
+    tick_lower: &TickState,
+    tick_upper: &TickState,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+    tick_current: i32,
+    reward_infos: &[RewardInfo; REWARD_NUM as usize],
+) -> [u128; REWARD_NUM as usize] {
+    let lower_outside = tick_lower.reward_growths_outside_x64;
+    let upper_outside = tick_upper.reward_growths_outside_x64;
+    let mut reward_growths_inside = [0; REWARD_NUM as usize];
+    for i in 0..REWARD_NUM as usize {
+        let reward_growth_global_x64 = reward_infos[i].reward_growth_global_x64;
+        let reward_growth_below_x64 = if tick_current >= tick_lower_index {
+            lower_outside[i]
+        } else {
+            reward_growth_global_x64.wrapping_sub(lower_outside[i])
+        };
+        let reward_growth_above_x64 = if tick_current < tick_upper_index {
+            upper_outside[i]
+        } else {
+            reward_growth_global_x64.wrapping_sub(upper_outside[i])
+        };
+        reward_growths_inside[i] = reward_growth_global_x64
+            .wrapping_sub(reward_growth_below_x64)
+            .wrapping_sub(reward_growth_above_x64);
+    }
+    reward_growths_inside
+}
+
 #[account(zero_copy)]
 #[repr(C, packed)]
 #[derive(Debug)]
@@ -93,6 +329,10 @@ pub struct TickStateArray {
     pub tick_states: [TickState; TICK_ARRAY_SIZE as usize],
 }
 
+// Tick arrays are created through a CPI to the system program, which allocates at most
+// 10240 bytes, so TickState has little room left to grow
+const _: () = assert!(ANCHOR_SIZE as usize + TickStateArray::LEN <= 10240);
+
 impl TickStateArray {
     pub const LEN: usize =
         32 +
@@ -139,11 +379,11 @@ impl TickStateArray {
                 payer, 
                 tick_array_account.clone(), 
                 system_program.to_account_info()], 
-            &[
-                &[TICK_ARRAY_SEED.as_bytes()],
-                &[pool_state_loader.key().as_ref()],
-                &[start_idx.to_le_bytes().as_ref()],
-                &[bump.to_le_bytes().as_ref()]]
+            &[&[
+                TICK_ARRAY_SEED.as_bytes(),
+                pool_state_loader.key().as_ref(),
+                start_idx.to_le_bytes().as_ref(),
+                bump.to_le_bytes().as_ref()]]
             )?;
             let loader = AccountLoad::<TickStateArray>::try_from_unchecked(
                 &crate::ID, 
@@ -157,6 +397,7 @@ impl TickStateArray {
     pub fn update_tick(
         &mut self,
         pool_state: &PoolState,
+        snapshot: &Observation,
         tick_index: i32, 
         liquidity_delta: i128,
         is_upper: bool,
@@ -167,7 +408,7 @@ impl TickStateArray {
             self.tick_start_idx, 
             self.tick_spacing)?;
         let tick = &mut self.tick_states[array_index];
-        let is_initializing = tick.update(pool_state, tick_index, liquidity_delta, is_upper)?;
+        let is_initializing = tick.update(pool_state, snapshot, tick_index, liquidity_delta, is_upper)?;
         if is_initializing {
             self.tick_valid_cnt += 1;
         }
@@ -178,6 +419,32 @@ impl TickStateArray {
         Ok(flipped)
     }
 
+    /// Applies f to a tick's limit orders, keeping tick_valid_cnt up to date.
+    /// Returns f's result and whether the array flipped.
+    pub fn update_orders<T>(
+        &mut self,
+        tick_index: i32,
+        f: impl FnOnce(&mut TickState) -> Result<T>,
+    ) -> Result<(T, bool)> {
+        let valid_before = self.valid();
+        let array_index = Self::tick_index_to_array_index(
+            tick_index,
+            self.tick_start_idx,
+            self.tick_spacing)?;
+        let tick = &mut self.tick_states[array_index];
+        let tick_valid_before = tick.valid();
+        let result = f(tick)?;
+        if !tick_valid_before && tick.valid() {
+            self.tick_valid_cnt += 1;
+        }
+        if tick_valid_before && !tick.valid() {
+            *tick = TickState::default();
+            self.tick_valid_cnt -= 1;
+        }
+        let flipped = self.valid() != valid_before;
+        Ok((result, flipped))
+    }
+
     pub fn tick_index_to_array_index(tick_index: i32, start_index: i32, tick_spacing: u16) -> Result<usize> {
         require!(tick_index >= start_index, ErrorCode::TickLowerThanArrayStart);
         let offset = (tick_index - start_index) / (tick_spacing as i32);
@@ -190,6 +457,60 @@ impl TickStateArray {
         self.tick_valid_cnt > 0
     }
 
+    pub fn get_tick_state(&self, tick_index: i32) -> Result<TickState> {
+        let array_index = Self::tick_index_to_array_index(
+            tick_index,
+            self.tick_start_idx,
+            self.tick_spacing)?;
+        Ok(self.tick_states[array_index])
+    }
+
+    /// Searches this array for the next initialized tick in the swap direction, initialized
+    /// meaning the tick has liquidity_gross or resting limit orders, as the swap has to stop
+    /// at either. Returns the tick index, get_tick_state reads the TickState behind it.
+    /// For zero_for_one the result is the largest initialized tick <= current_tick,
+    /// otherwise the smallest initialized tick > current_tick.
+    /// current_tick may sit outside the array, in which case the whole array is searched.
+    pub fn next_initialized_tick(&self, current_tick: i32, zero_for_one: bool) -> Option<i32> {
+        let tick_spacing = self.tick_spacing as i32;
+        let start_idx = self.tick_start_idx;
+        // current_tick - start_idx is never negative below, so the division
+        // rounds down for negative ticks as well
+        if zero_for_one {
+            if current_tick < start_idx {
+                return None;
+            }
+            let offset = ((current_tick - start_idx) / tick_spacing)
+                .min(TICK_ARRAY_SIZE as i32 - 1);
+            self.find_initialized_tick(0..=offset, true)
+        } else {
+            let offset = if current_tick < start_idx {
+                0
+            } else {
+                (current_tick - start_idx) / tick_spacing + 1
+            };
+            self.find_initialized_tick(offset..=TICK_ARRAY_SIZE as i32 - 1, false)
+        }
+    }
+
+    /// The first initialized tick met when entering the array in the swap direction:
+    /// the highest one for zero_for_one, otherwise the lowest
+    pub fn first_initialized_tick(&self, zero_for_one: bool) -> Option<i32> {
+        self.find_initialized_tick(0..=TICK_ARRAY_SIZE as i32 - 1, zero_for_one)
+    }
+
+    /// Scans the offsets from the top for zero_for_one, from the bottom otherwise
+    fn find_initialized_tick(&self, mut offsets: RangeInclusive<i32>, zero_for_one: bool) -> Option<i32> {
+        let tick_states = &self.tick_states;
+        let initialized = |i: &i32| tick_states[*i as usize].valid();
+        let offset = if zero_for_one {
+            offsets.rev().find(initialized)
+        } else {
+            offsets.find(initialized)
+        };
+        offset.map(|i| self.tick_start_idx + i * self.tick_spacing as i32)
+    }
+
     /// Input an arbitrary tick_index, output the start_index of the tick_array it sits on
     pub fn get_array_start_index(tick_index: i32, tick_spacing: u16) -> i32 {
         let ticks_in_array = TickStateArray::tick_count(tick_spacing);
@@ -205,6 +526,15 @@ impl TickStateArray {
     }
 }
 
+// At the smallest tick_spacing of 1 a tick array spans TICK_ARRAY_SIZE ticks, so each half
+// of the bitmap needs TICK_MAX / TICK_ARRAY_SIZE + 1 bits. Larger spacings need fewer, hence
+// the fixed size bitmap tracks every tick array of any pool and needs no extension.
+const _: () = assert!(
+    TICK_MAX / (TICK_ARRAY_SIZE as i32) < (TICK_ARRAY_BITMAP_SIZE * 64) as i32
+);
+
+/// One bit per tick array, set while the array has an initialized tick.
+/// bitmap_pos tracks the arrays starting at or above 0, bitmap_neg those below.
 #[account(zero_copy)]
 #[repr(C)]
 #[derive(Debug)]
@@ -236,15 +566,117 @@ impl TickStateArrayBitMap {
         Ok(())
     }
 
+    /// Whether the tick array holding tick_index has any initialized tick
+    pub fn is_initialized(&self, tick_index: i32, tick_spacing: u16) -> bool {
+        let (bitmap, (idx, bit_idx)) = if tick_index >= 0 {
+            (&self.bitmap_pos, Self::locate_pos(tick_index, tick_spacing))
+        } else {
+            (&self.bitmap_neg, Self::locate_neg(tick_index, tick_spacing))
+        };
+        bitmap[idx] & (1u64 << bit_idx) != 0
+    }
+
+    /// The start index of the nearest initialized tick array left (zero_for_one) or right
+    /// of the one starting at current_start_index, None if there is none up to the end of the range
+    pub fn next_initialized_tick_array_start_index(
+        &self,
+        current_start_index: i32,
+        tick_spacing: u16,
+        zero_for_one: bool,
+    ) -> Option<i32> {
+        tick_array_bitmap_math::next_initialized_tick_array_start_index(
+            &self.bitmap_pos,
+            &self.bitmap_neg,
+            current_start_index,
+            tick_spacing,
+            zero_for_one,
+        )
+    }
+
     pub fn locate_pos(tick_index: i32, tick_spacing: u16) -> (usize, usize) {
         let cnt = TickStateArray::tick_count(tick_spacing);
         let idx = tick_index / cnt;
         ((idx / 64) as usize, (idx % 64) as usize)
     }
 
+    /// The array starting at -tick_count lands on bit 0, the one before it on bit 1, and so on.
     pub fn locate_neg(tick_index: i32, tick_spacing: u16) -> (usize, usize) {
         let cnt = TickStateArray::tick_count(tick_spacing);
-        let idx = tick_index / cnt + 1;
+        let start_idx = TickStateArray::get_array_start_index(tick_index, tick_spacing);
+        let idx = -start_idx / cnt - 1;
         ((idx / 64) as usize, (idx % 64) as usize)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tick_test {
+    use super::*;
+
+    /// A tick array of spacing 10 starting at -600, with liquidity at the given offsets
+    fn tick_array(offsets: &[usize]) -> TickStateArray {
+        let mut tick_array = TickStateArray {
+            pool_id: Pubkey::default(),
+            tick_start_idx: -600,
+            tick_valid_cnt: 0,
+            tick_spacing: 10,
+            tick_states: [TickState::default(); TICK_ARRAY_SIZE as usize],
+        };
+        for offset in offsets {
+            tick_array.tick_states[*offset].liquidity_gross = 1;
+            tick_array.tick_valid_cnt += 1;
+        }
+        tick_array
+    }
+
+    #[test]
+    fn next_initialized_tick_zero_for_one() {
+        let tick_array = tick_array(&[0, 5, 59]);
+        assert_eq!(tick_array.next_initialized_tick(-545, true), Some(-550));
+        assert_eq!(tick_array.next_initialized_tick(-550, true), Some(-550));
+        assert_eq!(tick_array.next_initialized_tick(-551, true), Some(-600));
+        assert_eq!(tick_array.next_initialized_tick(-600, true), Some(-600));
+        // before the array there is nothing left in the swap direction
+        assert_eq!(tick_array.next_initialized_tick(-601, true), None);
+        // after the array the whole array is searched, from its last tick
+        assert_eq!(tick_array.next_initialized_tick(-10, true), Some(-10));
+        assert_eq!(tick_array.next_initialized_tick(1000, true), Some(-10));
+    }
+
+    #[test]
+    fn next_initialized_tick_one_for_zero() {
+        let tick_array = tick_array(&[0, 5, 59]);
+        // before the array the whole array is searched, from its first tick
+        assert_eq!(tick_array.next_initialized_tick(-601, false), Some(-600));
+        assert_eq!(tick_array.next_initialized_tick(-600, false), Some(-550));
+        assert_eq!(tick_array.next_initialized_tick(-555, false), Some(-550));
+        assert_eq!(tick_array.next_initialized_tick(-550, false), Some(-10));
+        assert_eq!(tick_array.next_initialized_tick(-11, false), Some(-10));
+        assert_eq!(tick_array.next_initialized_tick(-10, false), None);
+        // after the array there is nothing left in the swap direction
+        assert_eq!(tick_array.next_initialized_tick(0, false), None);
+    }
+
+    #[test]
+    fn next_initialized_tick_in_empty_array() {
+        let tick_array = tick_array(&[]);
+        assert_eq!(tick_array.next_initialized_tick(-300, true), None);
+        assert_eq!(tick_array.next_initialized_tick(-300, false), None);
+        assert_eq!(tick_array.first_initialized_tick(true), None);
+        assert_eq!(tick_array.first_initialized_tick(false), None);
+    }
+
+    #[test]
+    fn first_initialized_tick_from_either_end() {
+        let tick_array = tick_array(&[3, 40]);
+        assert_eq!(tick_array.first_initialized_tick(true), Some(-200));
+        assert_eq!(tick_array.first_initialized_tick(false), Some(-570));
+    }
+
+    #[test]
+    fn ticks_with_only_limit_orders_are_initialized() {
+        let mut tick_array = tick_array(&[]);
+        tick_array.tick_states[10].order_shares = 1;
+        assert_eq!(tick_array.next_initialized_tick(-300, true), Some(-500));
+        assert_eq!(tick_array.first_initialized_tick(false), Some(-500));
+    }
+}
@@ -1,7 +1,9 @@
 pub mod account_load;
+pub mod token;
 use std::{cell::{Ref, RefMut}, mem};
 
 pub use account_load::*;
+pub use token::*;
 use anchor_lang::{error::{Error, ErrorCode}, prelude::AccountInfo, Owner, ZeroCopy};
 
 /// Avoid &'a AccountInfo<'a> in AccountLoader
@@ -0,0 +1,199 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{
+        default_account_state::DefaultAccountState, transfer_fee::TransferFeeConfig, transfer_hook,
+        BaseStateWithExtensions,
+        ExtensionType, StateWithExtensions,
+    },
+    state::AccountState,
+};
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::error::ErrorCode;
+use crate::state::{PoolState, SupportMintAssociated};
+
+/// hook_accounts carries the transfer-hook program and its extra accounts
+/// when the mint has a transfer hook, it may hold unrelated accounts as well
+pub fn transfer_from_user_to_pool_vault<'info>(
+    signer: &Signer<'info>,
+    from: &InterfaceAccount<'info, TokenAccount>,
+    to_vault: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    hook_accounts: &[AccountInfo<'info>],
+    amount: u64,
+) -> Result<()> {
+    transfer_checked(
+        token_program,
+        from.to_account_info(),
+        mint,
+        to_vault.to_account_info(),
+        signer.to_account_info(),
+        hook_accounts,
+        amount,
+        &[],
+    )
+}
+
+/// The pool PDA owns both vaults, so withdrawals are signed with `PoolState::seeds()`
+pub fn transfer_from_pool_vault_to_user<'info>(
+    pool_state_loader: &AccountLoader<'info, PoolState>,
+    from_vault: &InterfaceAccount<'info, TokenAccount>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    hook_accounts: &[AccountInfo<'info>],
+    amount: u64,
+) -> Result<()> {
+    let pool_state = pool_state_loader.load()?;
+    transfer_checked(
+        token_program,
+        from_vault.to_account_info(),
+        mint,
+        to.to_account_info(),
+        pool_state_loader.to_account_info(),
+        hook_accounts,
+        amount,
+        &[&pool_state.seeds()],
+    )
+}
+
+/// Mints with a transfer hook go through the token-2022 helper, which resolves
+/// the extra account metas from hook_accounts and forwards them to the hook
+#[allow(clippy::too_many_arguments)]
+fn transfer_checked<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    from: AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    hook_accounts: &[AccountInfo<'info>],
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    if has_transfer_hook(mint)? {
+        spl_token_2022::onchain::invoke_transfer_checked(
+            token_program.key,
+            from,
+            mint.to_account_info(),
+            to,
+            authority,
+            hook_accounts,
+            amount,
+            mint.decimals,
+            signer_seeds,
+        )?;
+        return Ok(());
+    }
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TransferChecked {
+                from,
+                mint: mint.to_account_info(),
+                to,
+                authority,
+            },
+            signer_seeds,
+        ),
+        amount,
+        mint.decimals,
+    )
+}
+
+fn has_transfer_hook(mint: &InterfaceAccount<Mint>) -> Result<bool> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner == Token::id() {
+        return Ok(false);
+    }
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    Ok(transfer_hook::get_program_id(&mint).is_some())
+}
+
+/// The part of pre_fee_amount withheld by the mint's transfer-fee extension,
+/// i.e. the recipient gets pre_fee_amount - fee
+pub fn get_transfer_fee(mint: &InterfaceAccount<Mint>, pre_fee_amount: u64) -> Result<u64> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner == Token::id() {
+        return Ok(0);
+    }
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    let fee = match mint.get_extension::<TransferFeeConfig>() {
+        Ok(transfer_fee_config) => transfer_fee_config
+            .calculate_epoch_fee(Clock::get()?.epoch, pre_fee_amount)
+            .ok_or(ErrorCode::TransferFeeCalculateErr)?,
+        Err(_) => 0,
+    };
+    Ok(fee)
+}
+
+/// The fee to add on top of post_fee_amount so that the recipient gets exactly post_fee_amount
+pub fn get_transfer_inverse_fee(mint: &InterfaceAccount<Mint>, post_fee_amount: u64) -> Result<u64> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner == Token::id() || post_fee_amount == 0 {
+        return Ok(0);
+    }
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    let fee = match mint.get_extension::<TransferFeeConfig>() {
+        Ok(transfer_fee_config) => transfer_fee_config
+            .calculate_inverse_epoch_fee(Clock::get()?.epoch, post_fee_amount)
+            .ok_or(ErrorCode::TransferFeeCalculateErr)?,
+        Err(_) => 0,
+    };
+    Ok(fee)
+}
+
+/// Rejects Token-2022 mints with extensions that could freeze, drain or block
+/// the pool vaults. Extensions that only carry metadata or fees are fine.
+pub fn check_mint_extensions(mint: &InterfaceAccount<Mint>) -> Result<()> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner == Token::id() {
+        return Ok(());
+    }
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    for extension in mint.get_extension_types()? {
+        match extension {
+            ExtensionType::TransferFeeConfig
+            | ExtensionType::InterestBearingConfig
+            | ExtensionType::MetadataPointer
+            | ExtensionType::TokenMetadata
+            | ExtensionType::GroupPointer
+            | ExtensionType::TokenGroup
+            | ExtensionType::GroupMemberPointer
+            | ExtensionType::TokenGroupMember => {}
+            ExtensionType::DefaultAccountState => {
+                let default_state = mint.get_extension::<DefaultAccountState>()?;
+                require!(
+                    default_state.state != AccountState::Frozen as u8,
+                    ErrorCode::MintDefaultFrozenNotSupported
+                );
+            }
+            ExtensionType::PermanentDelegate => return err!(ErrorCode::MintPermanentDelegateNotSupported),
+            ExtensionType::NonTransferable => return err!(ErrorCode::MintNonTransferableNotSupported),
+            ExtensionType::TransferHook => return err!(ErrorCode::MintTransferHookNotSupported),
+            _ => return err!(ErrorCode::MintExtensionNotSupported),
+        }
+    }
+    Ok(())
+}
+
+/// Whether the admin has allowed the mint through a SupportMintAssociated account
+pub fn is_support_mint_associated(remaining_accounts: &[AccountInfo], mint: &Pubkey) -> bool {
+    remaining_accounts.iter().any(|account_info| {
+        account_info.owner == &crate::ID
+            && account_info
+                .try_borrow_data()
+                .ok()
+                .and_then(|data| SupportMintAssociated::try_deserialize(&mut &data[..]).ok())
+                .is_some_and(|support_mint| support_mint.mint == *mint)
+    })
+}